@@ -1,9 +1,12 @@
 # compiling
-mold --run cargo b --features serde --features hyper_engine
+mold --run cargo b --features serde --features hyper-native-tls
+mold --run cargo b --features serde --features hyper-rustls
 mold --run cargo b --features serde --features reqwasm_engine
-mold --run cargo b --features serde --features reqwest_engine
+mold --run cargo b --features serde --features reqwest-native-tls
+mold --run cargo b --features serde --features reqwest-rustls
 
 # testing
-mold --run cargo test --lib hyper --features serde --features hyper_engine
-mold --run cargo test --lib reqwest --features serde --features reqwest_engine
+mold --run cargo test --lib hyper --features serde --features hyper-native-tls
+mold --run cargo test --lib reqwest --features serde --features reqwest-native-tls
+mold --run cargo test --lib mock --features serde --features mock_engine
 #todo! WASM
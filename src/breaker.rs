@@ -0,0 +1,374 @@
+use crate::observe::{now, server};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Debug, Display, Formatter},
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Requests are sent and their outcomes counted.
+    Closed,
+    /// Requests fail with [`CircuitOpen`] until the cool-down has passed.
+    Open,
+    /// A single trial request is sent, its outcome closes or opens the circuit again.
+    HalfOpen,
+}
+
+/// A host changed its [`State`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// `host:port` of the circuit.
+    pub host: String,
+    pub from: State,
+    pub to: State,
+}
+
+/// Stops sending requests to a host once too many of them failed, until a cool-down has
+/// passed.
+///
+/// Failures are `5xx` responses and errors reaching the host, other errors such as an invalid
+/// header or a full queue are not counted.
+///
+/// Every host and port has its own circuit, clones share their circuits.
+#[derive(Clone)]
+pub struct Breaker {
+    /// The share of failed requests in the window that opens the circuit, from `0.0` to `1.0`.
+    pub failure_rate: f64,
+    /// The outcomes of the latest requests the failure rate is computed from.
+    pub window: usize,
+    /// Fewer outcomes than this never open the circuit.
+    pub minimum_requests: usize,
+    /// How long the circuit stays open before a trial request.
+    pub cool_down: Duration,
+    on_state_change: Option<Callback>,
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+type Callback = Arc<dyn Fn(&Event) + Send + Sync>;
+
+#[derive(Debug)]
+struct Circuit {
+    state: State,
+    /// `true` for every failed request in the window.
+    outcomes: VecDeque<bool>,
+    /// Milliseconds as returned by `now`.
+    opened_at: f64,
+    trial: bool,
+}
+
+/// The circuit of a host is open, the request was not sent. Holds its `host:port`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitOpen(pub String);
+
+impl Display for CircuitOpen {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "circuit open for {}", self.0)
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Self {
+            failure_rate: 0.5,
+            window: 20,
+            minimum_requests: 10,
+            cool_down: Duration::from_secs(30),
+            on_state_change: None,
+            circuits: Arc::default(),
+        }
+    }
+}
+
+impl Breaker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_failure_rate(&mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate;
+        self.to_owned()
+    }
+    pub fn set_window(&mut self, window: usize) -> Self {
+        self.window = window;
+        self.to_owned()
+    }
+    pub fn set_minimum_requests(&mut self, minimum_requests: usize) -> Self {
+        self.minimum_requests = minimum_requests;
+        self.to_owned()
+    }
+    pub fn set_cool_down(&mut self, cool_down: Duration) -> Self {
+        self.cool_down = cool_down;
+        self.to_owned()
+    }
+    /// Called after a circuit changed its state, with `tracing` the change is logged as well.
+    pub fn on_state_change(&mut self, callback: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        self.on_state_change = Some(Arc::new(callback));
+        self.to_owned()
+    }
+
+    /// The state of the circuit of `url`, closed for hosts without requests.
+    #[must_use]
+    pub fn state(&self, url: &str) -> State {
+        self.circuits()
+            .get(&host(url))
+            .map_or(State::Closed, |circuit| circuit.state)
+    }
+
+    /// Runs `request` unless the circuit of `url` is open, and counts its outcome. Without a
+    /// breaker `request` is run as is.
+    ///
+    /// Errors count as failures when `is_failure` says so, other errors are not counted.
+    pub(crate) async fn call<T, E: From<CircuitOpen>>(
+        breaker: Option<&Self>,
+        url: &str,
+        request: impl Future<Output = Result<T, E>>,
+        status: impl Fn(&T) -> u16,
+        is_failure: impl Fn(&E) -> bool,
+    ) -> Result<T, E> {
+        let mut call = match breaker {
+            Some(breaker) => Some(breaker.enter(host(url))?),
+            None => None,
+        };
+        let result = request.await;
+        if let Some(call) = &mut call {
+            match &result {
+                Ok(response) => call.finish(status(response) >= 500),
+                Err(error) if is_failure(error) => call.finish(true),
+                // an unfinished trial is given up, the next request tries again
+                Err(_) => {}
+            }
+        }
+        result
+    }
+
+    fn enter(&self, host: String) -> Result<Call<'_>, CircuitOpen> {
+        let mut circuits = self.circuits();
+        let circuit = circuits.entry(host.clone()).or_insert_with(|| Circuit {
+            state: State::Closed,
+            outcomes: VecDeque::new(),
+            opened_at: 0.0,
+            trial: false,
+        });
+        let cooled_down = now() - circuit.opened_at >= self.cool_down.as_secs_f64() * 1000.0;
+
+        let event = match circuit.state {
+            State::Closed => None,
+            State::Open if cooled_down => {
+                circuit.state = State::HalfOpen;
+                circuit.trial = true;
+                Some(Event {
+                    host: host.clone(),
+                    from: State::Open,
+                    to: State::HalfOpen,
+                })
+            }
+            State::HalfOpen if !circuit.trial => {
+                circuit.trial = true;
+                None
+            }
+            State::Open | State::HalfOpen => return Err(CircuitOpen(host)),
+        };
+        let trial = circuit.state == State::HalfOpen;
+        drop(circuits);
+
+        self.emit(event);
+        Ok(Call {
+            breaker: self,
+            host,
+            trial,
+            finished: false,
+        })
+    }
+
+    fn emit(&self, event: Option<Event>) {
+        let Some(event) = event else {
+            return;
+        };
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            host = event.host.as_str(),
+            from = ?event.from,
+            to = ?event.to,
+            "circuit state changed"
+        );
+        if let Some(callback) = &self.on_state_change {
+            callback(&event);
+        }
+    }
+
+    fn circuits(&self) -> MutexGuard<'_, HashMap<String, Circuit>> {
+        self.circuits.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A request let through by a circuit, a trial is given up when dropped unfinished.
+struct Call<'a> {
+    breaker: &'a Breaker,
+    host: String,
+    trial: bool,
+    finished: bool,
+}
+
+impl Call<'_> {
+    fn finish(&mut self, failed: bool) {
+        self.finished = true;
+        let breaker = self.breaker;
+        let mut circuits = breaker.circuits();
+        let Some(circuit) = circuits.get_mut(&self.host) else {
+            return;
+        };
+
+        let from = circuit.state;
+        match (circuit.state, self.trial) {
+            (State::HalfOpen, true) if failed => {
+                circuit.state = State::Open;
+                circuit.opened_at = now();
+                circuit.trial = false;
+            }
+            (State::HalfOpen, true) => {
+                circuit.state = State::Closed;
+                circuit.outcomes.clear();
+                circuit.trial = false;
+            }
+            (State::Closed, _) => {
+                circuit.outcomes.push_back(failed);
+                while circuit.outcomes.len() > breaker.window {
+                    circuit.outcomes.pop_front();
+                }
+                let failures = circuit.outcomes.iter().filter(|failed| **failed).count();
+                #[allow(clippy::cast_precision_loss)]
+                let rate = failures as f64 / circuit.outcomes.len() as f64;
+                if circuit.outcomes.len() >= breaker.minimum_requests
+                    && rate >= breaker.failure_rate
+                {
+                    circuit.state = State::Open;
+                    circuit.opened_at = now();
+                    circuit.outcomes.clear();
+                }
+            }
+            // requests sent before the circuit opened do not count
+            _ => {}
+        }
+
+        let event = (circuit.state != from).then(|| Event {
+            host: self.host.clone(),
+            from,
+            to: circuit.state,
+        });
+        drop(circuits);
+        breaker.emit(event);
+    }
+}
+
+impl Drop for Call<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.finished {
+            if let Some(circuit) = self.breaker.circuits().get_mut(&self.host) {
+                circuit.trial = false;
+            }
+        }
+    }
+}
+
+impl Debug for Breaker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Breaker")
+            .field("failure_rate", &self.failure_rate)
+            .field("window", &self.window)
+            .field("minimum_requests", &self.minimum_requests)
+            .field("cool_down", &self.cool_down)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `host:port` of `url`, circuits of the same host on other ports are separate.
+fn host(url: &str) -> String {
+    match server(url) {
+        (address, Some(port)) => format!("{address}:{port}"),
+        (address, None) => address.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::breaker::{Breaker, CircuitOpen, Event, State};
+    use std::{
+        sync::{Arc, Mutex, PoisonError},
+        time::Duration,
+    };
+
+    const URL: &str = "https://api.revolt.chat/users";
+
+    async fn send(breaker: &Breaker, result: Result<u16, CircuitOpen>) -> Result<u16, CircuitOpen> {
+        Breaker::call(
+            Some(breaker),
+            URL,
+            async { result },
+            |status| *status,
+            |_| true,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn open_and_recover() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut breaker = {
+            let events = events.clone();
+            Breaker::new()
+                .set_window(4)
+                .set_minimum_requests(2)
+                .set_cool_down(Duration::ZERO)
+                .on_state_change(move |event: &Event| {
+                    events
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(event.to);
+                })
+        };
+        let url = URL;
+        assert_eq!(send(&breaker, Ok(200)).await, Ok(200));
+        assert_eq!(send(&breaker, Ok(503)).await, Ok(503));
+        assert_eq!(breaker.state(url), State::Open);
+        // other hosts are not affected
+        assert_eq!(breaker.state("https://autumn.revolt.chat/"), State::Closed);
+
+        // the cool-down has passed, the trial request fails and opens the circuit again
+        assert_eq!(send(&breaker, Ok(500)).await, Ok(500));
+        assert_eq!(breaker.state(url), State::Open);
+        assert_eq!(send(&breaker, Ok(200)).await, Ok(200));
+        assert_eq!(breaker.state(url), State::Closed);
+
+        assert_eq!(
+            *events.lock().unwrap_or_else(PoisonError::into_inner),
+            [
+                State::Open,
+                State::HalfOpen,
+                State::Open,
+                State::HalfOpen,
+                State::Closed
+            ]
+        );
+
+        // errors count as failures when the engine blames the host
+        let breaker = breaker.set_cool_down(Duration::MAX);
+        let error = Err(CircuitOpen(String::new()));
+        for _ in 0..2 {
+            let request = async { error.clone() };
+            let _ = Breaker::call(Some(&breaker), URL, request, |status| *status, |_| false).await;
+        }
+        assert_eq!(breaker.state(url), State::Closed);
+        let _ = send(&breaker, error.clone()).await;
+        let _ = send(&breaker, error).await;
+        assert_eq!(
+            send(&breaker, Ok(200)).await,
+            Err(CircuitOpen(String::from("api.revolt.chat:443")))
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use hyper::{
+    header::{
+        HeaderName, HeaderValue, AGE, CACHE_CONTROL, CONTENT_LENGTH, DATE, ETAG, EXPIRES,
+        IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, VARY,
+    },
+    HeaderMap, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Debug, Formatter},
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Where a [`Cache`] keeps its responses, every variant of a URL is stored under one key.
+pub trait Store: Send + Sync {
+    fn get(&self, key: &str) -> Vec<Entry>;
+    /// Replaces the variants of `key`, removing it when `entries` is empty.
+    fn put(&self, key: &str, entries: Vec<Entry>);
+}
+
+/// A stored response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub status: u16,
+    /// The URL of the final response, after following redirects.
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// The request headers named by `Vary`, a request must send the same to be served.
+    pub vary: Vec<(String, Option<String>)>,
+    /// When the response was received or last revalidated, in seconds since the Unix epoch.
+    pub stored: u64,
+}
+
+/// Caches responses to `GET` and `HEAD` requests as a private cache, following `Cache-Control`,
+/// `Expires` and `Vary`.
+///
+/// Stale responses with an `ETag` or `Last-Modified` are revalidated with `If-None-Match` and
+/// `If-Modified-Since`, a `304 Not Modified` returns the stored body. Clones share the store.
+#[derive(Clone)]
+pub struct Cache {
+    store: Arc<dyn Store>,
+}
+
+/// What to do with a request.
+pub(crate) enum Lookup {
+    /// Answer with the stored response.
+    Fresh(Entry),
+    /// Send the request with these validators, `304` keeps the stored response.
+    Stale(Entry, HeaderMap),
+    Miss,
+}
+
+impl Cache {
+    pub fn new(store: impl Store + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Keeps responses for the lifetime of the cache and its clones, up to the default size of
+    /// a [`Memory`] store.
+    #[must_use]
+    pub fn memory() -> Self {
+        Self::new(Memory::default())
+    }
+
+    /// Keeps responses as JSON files in `directory`, created when missing.
+    pub fn disk(directory: impl Into<PathBuf>) -> Self {
+        Self::new(Disk {
+            directory: directory.into(),
+        })
+    }
+
+    pub(crate) fn lookup(&self, method: &str, url: &str, headers: &HeaderMap) -> Lookup {
+        let request = directives(headers);
+        // conditional requests of the caller are theirs to answer
+        if !matches!(method, "GET" | "HEAD")
+            || request.contains_key("no-store")
+            || headers.contains_key(IF_NONE_MATCH)
+            || headers.contains_key(IF_MODIFIED_SINCE)
+        {
+            return Lookup::Miss;
+        }
+
+        let Some(entry) = self
+            .store
+            .get(&key(method, url))
+            .into_iter()
+            .find(|entry| entry.varies_with(headers))
+        else {
+            return Lookup::Miss;
+        };
+
+        let response = directives(&entry.headers());
+        let revalidate = request.contains_key("no-cache")
+            || request
+                .get("max-age")
+                .is_some_and(|age| age.as_deref() == Some("0"))
+            || response.contains_key("no-cache");
+        if !revalidate && entry.age() < entry.lifetime() {
+            return Lookup::Fresh(entry);
+        }
+
+        let mut validators = HeaderMap::new();
+        let stored = entry.headers();
+        if let Some(etag) = stored.get(ETAG) {
+            validators.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(modified) = stored.get(LAST_MODIFIED) {
+            validators.insert(IF_MODIFIED_SINCE, modified.clone());
+        }
+        if validators.is_empty() {
+            Lookup::Miss
+        } else {
+            Lookup::Stale(entry, validators)
+        }
+    }
+
+    /// Stores a cacheable response, a successful unsafe method drops the stored ones of `url`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn store(
+        &self,
+        method: &str,
+        url: &str,
+        request_headers: &HeaderMap,
+        status: StatusCode,
+        response_url: String,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) {
+        if !matches!(method, "GET" | "HEAD" | "OPTIONS" | "TRACE") {
+            if status.is_success() || status.is_redirection() {
+                for method in ["GET", "HEAD"] {
+                    self.store.put(&key(method, url), Vec::new());
+                }
+            }
+            return;
+        }
+
+        let response = directives(headers);
+        let vary = headers
+            .get_all(VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+
+        if !matches!(method, "GET" | "HEAD")
+            || !matches!(
+                status.as_u16(),
+                200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+            )
+            || directives(request_headers).contains_key("no-store")
+            || response.contains_key("no-store")
+            || vary.iter().any(|name| name == "*")
+        {
+            return;
+        }
+
+        let entry = Entry {
+            status: status.as_u16(),
+            url: response_url,
+            headers: pairs(headers),
+            body: body.to_vec(),
+            vary: vary
+                .into_iter()
+                .map(|name| {
+                    let value = joined(request_headers, &name);
+                    (name, value)
+                })
+                .collect(),
+            stored: now(),
+        };
+        let revalidatable = headers.contains_key(ETAG) || headers.contains_key(LAST_MODIFIED);
+        if entry.lifetime() == 0 && !revalidatable {
+            return;
+        }
+
+        let key = key(method, url);
+        let mut entries = self.store.get(&key);
+        entries.retain(|stored| stored.vary != entry.vary);
+        entries.push(entry);
+        self.store.put(&key, entries);
+    }
+
+    /// Refreshes `entry` with the headers of a `304 Not Modified` and returns it.
+    pub(crate) fn revalidated(
+        &self,
+        method: &str,
+        url: &str,
+        mut entry: Entry,
+        headers: &HeaderMap,
+    ) -> Entry {
+        // the length is that of the empty 304 body
+        let mut stored = entry.headers();
+        for name in headers.keys().filter(|name| *name != CONTENT_LENGTH) {
+            stored.remove(name);
+        }
+        for (name, value) in headers.iter().filter(|(name, _)| *name != CONTENT_LENGTH) {
+            stored.append(name, value.clone());
+        }
+        entry.headers = pairs(&stored);
+        entry.stored = now();
+
+        let key = key(method, url);
+        let mut entries = self.store.get(&key);
+        entries.retain(|stored| stored.vary != entry.vary);
+        entries.push(entry.clone());
+        self.store.put(&key, entries);
+        entry
+    }
+}
+
+impl Debug for Cache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache").finish_non_exhaustive()
+    }
+}
+
+impl Entry {
+    /// Entries read from disk are checked when loaded.
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK)
+    }
+
+    #[must_use]
+    pub fn headers(&self) -> HeaderMap {
+        self.headers
+            .iter()
+            .filter_map(|(key, value)| {
+                Some((
+                    HeaderName::from_bytes(key.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Seconds the response stays fresh for, from `max-age` or `Expires`.
+    fn lifetime(&self) -> u64 {
+        let headers = self.headers();
+        if let Some(Some(age)) = directives(&headers).get("max-age") {
+            return age.parse().unwrap_or(0);
+        }
+        let date = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| httpdate::parse_http_date(value).ok())
+        };
+        // an invalid `Expires`, such as `0`, means already expired
+        date(EXPIRES).map_or(0, |expires| {
+            seconds(expires).saturating_sub(date(DATE).map_or(self.stored, seconds))
+        })
+    }
+
+    fn age(&self) -> u64 {
+        let age = self
+            .headers()
+            .get(AGE)
+            .and_then(|age| age.to_str().ok())
+            .and_then(|age| age.parse::<u64>().ok())
+            .unwrap_or(0);
+        age + now().saturating_sub(self.stored)
+    }
+
+    /// Roughly the memory the entry takes.
+    fn size(&self) -> usize {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| name.len() + value.len());
+        let vary = self
+            .vary
+            .iter()
+            .map(|(name, value)| name.len() + value.as_ref().map_or(0, String::len));
+        self.url.len() + self.body.len() + headers.chain(vary).sum::<usize>()
+    }
+
+    fn varies_with(&self, headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| joined(headers, name) == *value)
+    }
+}
+
+/// Keeps entries in memory, evicting the least recently used keys once their size exceeds
+/// `max_bytes`. The default keeps up to 64 MiB.
+#[derive(Debug)]
+pub struct Memory {
+    /// The size of the stored URLs, headers and bodies, a key larger than it is not stored.
+    pub max_bytes: usize,
+    state: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    /// The entries of a key, their size and their last use.
+    keys: HashMap<String, (Vec<Entry>, usize, u64)>,
+    /// Keys by their last use, the least recent first.
+    uses: BTreeMap<u64, String>,
+    clock: u64,
+    bytes: usize,
+}
+
+impl Memory {
+    #[must_use]
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::default(),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, Lru> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new(64 * 1024 * 1024)
+    }
+}
+
+impl Store for Memory {
+    fn get(&self, key: &str) -> Vec<Entry> {
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+        let Some((entries, _, used)) = state.keys.get_mut(key) else {
+            return Vec::new();
+        };
+        let (entries, previous) = (entries.clone(), std::mem::replace(used, clock));
+        state.uses.remove(&previous);
+        state.uses.insert(clock, key.to_owned());
+        entries
+    }
+    fn put(&self, key: &str, entries: Vec<Entry>) {
+        let mut state = self.state();
+        if let Some((_, size, used)) = state.keys.remove(key) {
+            state.uses.remove(&used);
+            state.bytes -= size;
+        }
+        let size = entries.iter().map(Entry::size).sum::<usize>();
+        if entries.is_empty() || size > self.max_bytes {
+            return;
+        }
+
+        while state.bytes + size > self.max_bytes {
+            let Some((_, evicted)) = state.uses.pop_first() else {
+                break;
+            };
+            if let Some((_, size, _)) = state.keys.remove(&evicted) {
+                state.bytes -= size;
+            }
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        state.uses.insert(clock, key.to_owned());
+        state.keys.insert(key.to_owned(), (entries, size, clock));
+        state.bytes += size;
+    }
+}
+
+/// Keeps entries as one JSON file per key, failing to read or write a file is a cache miss.
+#[derive(Debug, Clone)]
+pub struct Disk {
+    pub directory: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    key: String,
+    entries: Vec<Entry>,
+}
+
+impl Disk {
+    // FNV-1a, stable across builds unlike the hasher of the standard library
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        self.directory.join(format!("{hash:016x}.json"))
+    }
+}
+
+impl Store for Disk {
+    fn get(&self, key: &str) -> Vec<Entry> {
+        File::open(self.path(key))
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, Stored>(BufReader::new(file)).ok())
+            .filter(|file| file.key == key)
+            .map(|file| file.entries)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| StatusCode::from_u16(entry.status).is_ok())
+            .collect()
+    }
+    fn put(&self, key: &str, entries: Vec<Entry>) {
+        let path = self.path(key);
+        if entries.is_empty() {
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+        let _ = std::fs::create_dir_all(&self.directory)
+            .and_then(|()| File::create(path))
+            .map(|file| {
+                serde_json::to_writer(
+                    BufWriter::new(file),
+                    &Stored {
+                        key: key.to_owned(),
+                        entries,
+                    },
+                )
+            });
+    }
+}
+
+fn key(method: &str, url: &str) -> String {
+    format!("{method} {url}")
+}
+
+/// `Cache-Control` directives with lowercase names and unquoted values.
+fn directives(headers: &HeaderMap) -> HashMap<String, Option<String>> {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter(|directive| !directive.trim().is_empty())
+        .map(|directive| match directive.split_once('=') {
+            Some((name, value)) => (
+                name.trim().to_lowercase(),
+                Some(value.trim().trim_matches('"').to_owned()),
+            ),
+            None => (directive.trim().to_lowercase(), None),
+        })
+        .collect()
+}
+
+fn joined(headers: &HeaderMap, name: &str) -> Option<String> {
+    let values = headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>();
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+fn pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+fn now() -> u64 {
+    seconds(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{Cache, Entry, Lookup, Memory, Store};
+    use hyper::{header::HeaderValue, HeaderMap, StatusCode};
+
+    #[test]
+    fn freshness_and_vary() {
+        let directory =
+            std::env::temp_dir().join(format!("reywen-http-cache-{}", std::process::id()));
+        let cache = Cache::disk(&directory);
+        let url = "https://api.hypixel.net/skyblock/bazaar";
+
+        let mut request = HeaderMap::new();
+        request.insert("accept-language", HeaderValue::from_static("en"));
+        let mut response = HeaderMap::new();
+        response.insert("cache-control", HeaderValue::from_static("max-age=60"));
+        response.insert("vary", HeaderValue::from_static("Accept-Language"));
+        cache.store(
+            "GET",
+            url,
+            &request,
+            StatusCode::OK,
+            url.to_owned(),
+            &response,
+            b"{}",
+        );
+
+        assert!(matches!(
+            cache.lookup("GET", url, &request),
+            Lookup::Fresh(entry) if entry.body == b"{}"
+        ));
+        // another variant
+        assert!(matches!(
+            cache.lookup("GET", url, &HeaderMap::new()),
+            Lookup::Miss
+        ));
+
+        // revalidated once stale
+        response.insert("cache-control", HeaderValue::from_static("no-cache"));
+        response.insert("etag", HeaderValue::from_static("\"v1\""));
+        cache.store(
+            "GET",
+            url,
+            &request,
+            StatusCode::OK,
+            url.to_owned(),
+            &response,
+            b"{}",
+        );
+        assert!(matches!(
+            cache.lookup("GET", url, &request),
+            Lookup::Stale(_, validators) if validators["if-none-match"] == "\"v1\""
+        ));
+
+        // unsafe methods invalidate
+        cache.store(
+            "POST",
+            url,
+            &request,
+            StatusCode::OK,
+            url.to_owned(),
+            &HeaderMap::new(),
+            b"",
+        );
+        assert!(matches!(cache.lookup("GET", url, &request), Lookup::Miss));
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn memory_eviction() {
+        let entry = |body: &[u8]| Entry {
+            status: 200,
+            url: String::new(),
+            headers: Vec::new(),
+            body: body.to_vec(),
+            vary: Vec::new(),
+            stored: 0,
+        };
+        let memory = Memory::new(8);
+        memory.put("a", vec![entry(b"aaa")]);
+        memory.put("b", vec![entry(b"bbb")]);
+        // a is used more recently than b
+        assert_eq!(memory.get("a").len(), 1);
+
+        memory.put("c", vec![entry(b"ccc")]);
+        assert!(memory.get("b").is_empty());
+        assert_eq!(memory.get("a").len(), 1);
+        assert_eq!(memory.get("c").len(), 1);
+
+        // too large to be stored at all, replacing the stored entries
+        memory.put("a", vec![entry(b"aaaaaaaaa")]);
+        assert!(memory.get("a").is_empty());
+        assert_eq!(memory.get("c").len(), 1);
+    }
+}
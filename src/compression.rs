@@ -0,0 +1,183 @@
+use std::io::{Read, Write};
+
+/// Content codings enabled through the `gzip`, `deflate`, `brotli` and `zstd` features, `zstd`
+/// is not available on WASM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "deflate")]
+    Deflate,
+    #[cfg(feature = "brotli")]
+    Brotli,
+    #[cfg(all(feature = "zstd", not(target_arch = "wasm32")))]
+    Zstd,
+}
+
+impl Encoding {
+    pub const ALL: &'static [Self] = &[
+        #[cfg(feature = "gzip")]
+        Self::Gzip,
+        #[cfg(feature = "deflate")]
+        Self::Deflate,
+        #[cfg(feature = "brotli")]
+        Self::Brotli,
+        #[cfg(all(feature = "zstd", not(target_arch = "wasm32")))]
+        Self::Zstd,
+    ];
+
+    /// The token used in `Accept-Encoding` and `Content-Encoding`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => "gzip",
+            #[cfg(feature = "deflate")]
+            Self::Deflate => "deflate",
+            #[cfg(feature = "brotli")]
+            Self::Brotli => "br",
+            #[cfg(all(feature = "zstd", not(target_arch = "wasm32")))]
+            Self::Zstd => "zstd",
+        }
+    }
+
+    #[must_use]
+    pub fn from_token(token: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| token.trim().eq_ignore_ascii_case(encoding.as_str()))
+    }
+
+    pub fn decode(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                flate2::read::MultiGzDecoder::new(data).read_to_end(&mut decoded)?;
+            }
+            // `deflate` is meant to be zlib wrapped, some servers send the raw stream anyway
+            #[cfg(feature = "deflate")]
+            Self::Deflate => {
+                if flate2::read::ZlibDecoder::new(data)
+                    .read_to_end(&mut decoded)
+                    .is_err()
+                {
+                    decoded.clear();
+                    flate2::read::DeflateDecoder::new(data).read_to_end(&mut decoded)?;
+                }
+            }
+            #[cfg(feature = "brotli")]
+            Self::Brotli => {
+                brotli::Decompressor::new(data, 4096).read_to_end(&mut decoded)?;
+            }
+            #[cfg(all(feature = "zstd", not(target_arch = "wasm32")))]
+            Self::Zstd => {
+                zstd::stream::read::Decoder::new(data)?.read_to_end(&mut decoded)?;
+            }
+        }
+        Ok(decoded)
+    }
+
+    pub fn encode(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "deflate")]
+            Self::Deflate => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "brotli")]
+            Self::Brotli => {
+                let mut encoded = Vec::new();
+                brotli::CompressorReader::new(data, 4096, 9, 22).read_to_end(&mut encoded)?;
+                Ok(encoded)
+            }
+            #[cfg(all(feature = "zstd", not(target_arch = "wasm32")))]
+            Self::Zstd => zstd::stream::encode_all(data, 0),
+        }
+    }
+}
+
+/// `Accept-Encoding` value listing every enabled encoding.
+#[must_use]
+pub fn accept_encoding() -> String {
+    Encoding::ALL
+        .iter()
+        .map(|encoding| encoding.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether a response to `method` with `status` carries a body, responses to `HEAD` and `1xx`,
+/// `204` and `304` responses keep the `Content-Encoding` of the body they stand for.
+#[must_use]
+pub fn has_body(method: &str, status: u16) -> bool {
+    method != "HEAD" && !matches!(status, 100..=199 | 204 | 304)
+}
+
+/// Reverses the codings listed in a `Content-Encoding` header.
+///
+/// The body is returned as received when any of them is not enabled, or when it is empty.
+pub fn decode(content_encoding: &str, data: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    if data.is_empty() {
+        return Ok(data);
+    }
+    let Some(encodings) = content_encoding
+        .split(',')
+        .filter(|token| !token.trim().eq_ignore_ascii_case("identity"))
+        .map(Encoding::from_token)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(data);
+    };
+
+    encodings
+        .into_iter()
+        .rev()
+        .try_fold(data, |data, encoding| encoding.decode(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_round_trip() -> std::io::Result<()> {
+        let compressed = crate::compression::Encoding::Gzip.encode(br#"{"success":true}"#)?;
+
+        assert!(crate::compression::accept_encoding().contains("gzip"));
+        assert_eq!(
+            crate::compression::decode("identity, GZIP", compressed)?,
+            br#"{"success":true}"#.to_vec()
+        );
+        assert_eq!(crate::compression::decode("unknown", vec![1])?, vec![1]);
+        assert_eq!(
+            crate::compression::decode("gzip", Vec::new())?,
+            Vec::<u8>::new()
+        );
+        assert!(!crate::compression::has_body("HEAD", 200));
+        assert!(!crate::compression::has_body("GET", 304));
+        Ok(())
+    }
+
+    #[test]
+    fn every_encoding_round_trips() -> std::io::Result<()> {
+        for encoding in crate::compression::Encoding::ALL {
+            let compressed = encoding.encode(&[7; 1024])?;
+            assert!(compressed.len() < 1024);
+            assert_eq!(
+                crate::compression::decode(encoding.as_str(), compressed)?,
+                vec![7; 1024]
+            );
+        }
+        Ok(())
+    }
+}
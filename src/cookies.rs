@@ -0,0 +1,138 @@
+use cookie::Cookie;
+use cookie_store::CookieStore;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use url::Url;
+
+/// In-memory cookie store, cookies are matched against domain, path, expiry and the `Secure`
+/// attribute before being sent.
+///
+/// The store is reference counted so every clone of a client (and of the jar itself) reads and
+/// writes the same cookies.
+#[derive(Debug, Clone, Default)]
+pub struct Jar(Arc<RwLock<CookieStore>>);
+
+impl Jar {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a jar previously written by [`Jar::save`], a missing file results in an empty jar.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(error) => return Err(error),
+        };
+        let store = CookieStore::load_json(BufReader::new(file)).map_err(std::io::Error::other)?;
+        Ok(Self(Arc::new(RwLock::new(store))))
+    }
+
+    /// Writes all persistent, unexpired cookies to `path` as JSON. Session cookies are not saved.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.read()
+            .save_json(&mut writer)
+            .map_err(std::io::Error::other)
+    }
+
+    /// Stores the cookies of `Set-Cookie` header values received from `url`.
+    pub fn store_response_cookies<'a>(&self, url: &Url, set_cookie: impl Iterator<Item = &'a str>) {
+        self.write().store_response_cookies(
+            set_cookie.filter_map(|value| Cookie::parse(value.to_owned()).ok()),
+            url,
+        );
+    }
+
+    /// The `Cookie` header value to send to `url`, if any cookie matches it.
+    #[must_use]
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        let header = self
+            .read()
+            .get_request_values(url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty()).then_some(header)
+    }
+
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    // a poisoned lock still holds a consistent store, cookies are only ever replaced whole
+    fn read(&self) -> RwLockReadGuard<'_, CookieStore> {
+        self.0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, CookieStore> {
+        self.0
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "__reqwest")]
+impl reqwest::cookie::CookieStore for Jar {
+    fn set_cookies(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &reqwest::header::HeaderValue>,
+        url: &Url,
+    ) {
+        self.store_response_cookies(url, cookie_headers.filter_map(|value| value.to_str().ok()));
+    }
+
+    fn cookies(&self, url: &Url) -> Option<reqwest::header::HeaderValue> {
+        self.cookie_header(url)
+            .and_then(|header| reqwest::header::HeaderValue::from_str(&header).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookies::Jar;
+    use url::Url;
+
+    #[test]
+    fn cookie_rules() -> Result<(), url::ParseError> {
+        let jar = Jar::new();
+        let url = Url::parse("https://api.example.com/auth/login")?;
+        jar.store_response_cookies(
+            &url,
+            [
+                "session=abc; Path=/; Secure",
+                "scoped=1; Path=/auth",
+                "expired=1; Max-Age=0",
+            ]
+            .into_iter(),
+        );
+
+        let header = jar
+            .cookie_header(&Url::parse("https://api.example.com/auth/me")?)
+            .unwrap_or_default();
+        assert!(header.contains("session=abc") && header.contains("scoped=1"));
+        assert!(!header.contains("expired"));
+
+        // path and secure rules
+        assert_eq!(
+            jar.cookie_header(&Url::parse("https://api.example.com/users")?),
+            Some(String::from("session=abc"))
+        );
+        assert_eq!(
+            jar.cookie_header(&Url::parse("http://api.example.com/auth")?),
+            Some(String::from("scoped=1"))
+        );
+        // clones share the store
+        let shared = jar.clone();
+        shared.clear();
+        assert_eq!(jar.cookie_header(&url), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+pub type Resolving = Pin<Box<dyn Future<Output = io::Result<Vec<IpAddr>>> + Send>>;
+
+/// Resolves host names for the native engines, for example through a caching resolver.
+pub trait Resolve: Send + Sync {
+    fn resolve(&self, host: &str) -> Resolving;
+}
+
+impl<F> Resolve for F
+where
+    F: Fn(&str) -> Resolving + Send + Sync,
+{
+    fn resolve(&self, host: &str) -> Resolving {
+        self(host)
+    }
+}
+
+/// Which address family is tried first when a host has both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpPreference {
+    /// Keeps the order of the resolver.
+    #[default]
+    Resolver,
+    PreferV4,
+    PreferV6,
+    V4Only,
+    V6Only,
+}
+
+/// How the native engines find the addresses of a host.
+///
+/// Static overrides are checked first, then the custom resolver, then the system resolver.
+#[derive(Clone)]
+pub struct Dns {
+    pub resolver: Option<Arc<dyn Resolve>>,
+    /// Lower case host names and the addresses they resolve to.
+    pub overrides: HashMap<String, Vec<IpAddr>>,
+    pub ip_preference: IpPreference,
+    /// How long the preferred family is tried before racing the other one, `None` disables
+    /// racing. Only used by the hyper engine, reqwest always waits 300ms.
+    pub happy_eyeballs_timeout: Option<Duration>,
+}
+
+impl Dns {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_resolver(&mut self, resolver: impl Resolve + 'static) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self.to_owned()
+    }
+    /// Resolves `host` to `addresses` without asking any resolver.
+    pub fn add_override(
+        &mut self,
+        host: impl Into<String>,
+        addresses: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        self.overrides
+            .insert(host.into().to_lowercase(), addresses.into_iter().collect());
+        self.to_owned()
+    }
+    pub fn set_ip_preference(&mut self, preference: IpPreference) -> Self {
+        self.ip_preference = preference;
+        self.to_owned()
+    }
+    pub fn set_happy_eyeballs_timeout(&mut self, timeout: Option<Duration>) -> Self {
+        self.happy_eyeballs_timeout = timeout;
+        self.to_owned()
+    }
+
+    /// The addresses of `host` in the order they are tried, ports are left at 0.
+    pub async fn lookup(&self, host: &str) -> io::Result<Vec<SocketAddr>> {
+        let mut addresses = match self.overrides.get(&host.to_lowercase()) {
+            Some(addresses) => addresses.clone(),
+            None => match &self.resolver {
+                Some(resolver) => resolver.resolve(host).await?,
+                None => tokio::net::lookup_host((host, 0))
+                    .await?
+                    .map(|address| address.ip())
+                    .collect(),
+            },
+        };
+
+        match self.ip_preference {
+            IpPreference::Resolver => {}
+            IpPreference::PreferV4 => addresses.sort_by_key(IpAddr::is_ipv6),
+            IpPreference::PreferV6 => addresses.sort_by_key(IpAddr::is_ipv4),
+            IpPreference::V4Only => addresses.retain(IpAddr::is_ipv4),
+            IpPreference::V6Only => addresses.retain(IpAddr::is_ipv6),
+        }
+        if addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no addresses found for {host}"),
+            ));
+        }
+        Ok(addresses
+            .into_iter()
+            .map(|address| SocketAddr::new(address, 0))
+            .collect())
+    }
+}
+
+impl Default for Dns {
+    fn default() -> Self {
+        Self {
+            resolver: None,
+            overrides: HashMap::new(),
+            ip_preference: IpPreference::default(),
+            happy_eyeballs_timeout: Some(Duration::from_millis(300)),
+        }
+    }
+}
+
+impl Debug for Dns {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dns")
+            .field("resolver", &self.resolver.as_ref().map(|_| "Custom"))
+            .field("overrides", &self.overrides)
+            .field("ip_preference", &self.ip_preference)
+            .field("happy_eyeballs_timeout", &self.happy_eyeballs_timeout)
+            .finish()
+    }
+}
+
+#[cfg(feature = "__reqwest")]
+impl reqwest::dns::Resolve for Dns {
+    fn resolve(&self, name: hyper::client::connect::dns::Name) -> reqwest::dns::Resolving {
+        let dns = self.clone();
+        Box::pin(async move {
+            let addresses = dns.lookup(name.as_str()).await?;
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dns::{Dns, IpPreference, Resolving};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    #[tokio::test]
+    async fn overrides_and_preference() -> std::io::Result<()> {
+        let v4 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        let dns = Dns::new()
+            .set_resolver(move |_: &str| -> Resolving { Box::pin(async move { Ok(vec![v6, v4]) }) })
+            .add_override("API.revolt.chat", [v4]);
+
+        let ips = |addresses: Vec<std::net::SocketAddr>| {
+            addresses
+                .into_iter()
+                .map(|address| address.ip())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ips(dns.lookup("api.revolt.chat").await?), vec![v4]);
+        assert_eq!(ips(dns.lookup("cdn.revolt.chat").await?), vec![v6, v4]);
+        assert_eq!(
+            ips(dns
+                .clone()
+                .set_ip_preference(IpPreference::PreferV4)
+                .lookup("cdn.revolt.chat")
+                .await?),
+            vec![v4, v6]
+        );
+        assert!(dns
+            .clone()
+            .add_override("v4.revolt.chat", [v4])
+            .set_ip_preference(IpPreference::V6Only)
+            .lookup("v4.revolt.chat")
+            .await
+            .is_err());
+        Ok(())
+    }
+}
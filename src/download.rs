@@ -0,0 +1,348 @@
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{Debug, Display, Formatter, Write},
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::watch,
+};
+
+/// How far a download got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes in the file so far, including those of a resumed partial file.
+    pub downloaded: u64,
+    /// The size of the whole file, when the server sent it.
+    pub total: Option<u64>,
+    /// The size of the partial file the download resumed from.
+    pub resumed_from: u64,
+}
+
+/// The downloaded file is not the one expected, a partial file is kept when it may still be
+/// resumed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    Length {
+        expected: u64,
+        received: u64,
+    },
+    /// The SHA-256 of the file, as lowercase hex.
+    Checksum {
+        expected: String,
+        computed: String,
+    },
+    /// A partial response did not start where the partial file ends.
+    Range,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Length { expected, received } => {
+                write!(f, "expected {expected} bytes, received {received}")
+            }
+            Self::Checksum { expected, computed } => {
+                write!(f, "expected SHA-256 {expected}, computed {computed}")
+            }
+            Self::Range => write!(f, "partial response does not continue the partial file"),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// The headers of a download response.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Head {
+    pub status: u16,
+    /// `Content-Length`, the size of this response.
+    pub length: Option<u64>,
+    /// Where a `206 Partial Content` response starts, from `Content-Range`.
+    pub start: Option<u64>,
+    /// The size of the whole file, from `Content-Range`.
+    pub total: Option<u64>,
+    /// A strong `ETag`, else `Last-Modified`, sent as `If-Range` when resuming.
+    pub validator: Option<String>,
+}
+
+impl Head {
+    /// Reads the headers through `header`, which looks up a header by its lowercase name.
+    pub(crate) fn new<'a>(status: u16, header: impl Fn(&str) -> Option<&'a str>) -> Self {
+        let range = header("content-range")
+            .and_then(|range| range.strip_prefix("bytes "))
+            .and_then(|range| range.split_once('/'));
+        Self {
+            status,
+            length: header("content-length").and_then(|length| length.parse().ok()),
+            start: range
+                .and_then(|(range, _)| range.split_once('-'))
+                .and_then(|(start, _)| start.parse().ok()),
+            total: range.and_then(|(_, total)| total.parse().ok()),
+            validator: header("etag")
+                .filter(|etag| !etag.starts_with("W/"))
+                .or_else(|| header("last-modified"))
+                .map(str::to_owned),
+        }
+    }
+}
+
+/// An engine that downloads.
+pub trait Open: Sync {
+    /// The body of an open response.
+    type Response: Send;
+    type Error: From<io::Error> + From<Mismatch> + Send;
+
+    /// GETs `path` without compression, from `Range: bytes=<offset>-` with `If-Range` when
+    /// resuming. Other statuses than `200 OK`, `206 Partial Content` and, when resuming,
+    /// `416 Range Not Satisfiable` are errors.
+    fn open(
+        &self,
+        path: &str,
+        resume: Option<(u64, &str)>,
+    ) -> impl Future<Output = Result<(Head, Self::Response), Self::Error>> + Send;
+
+    /// The next bytes of the body, `None` once complete.
+    fn chunk(
+        response: &mut Self::Response,
+    ) -> impl Future<Output = Option<Result<Vec<u8>, Self::Error>>> + Send;
+}
+
+/// Streams a response to a file, as used by the `download_to` method of an engine.
+///
+/// The body is written to `<file>.part` and renamed once complete and verified. A download
+/// that failed midway resumes from the partial file, when the server sent a validator and
+/// still has the same file.
+///
+/// Downloads bypass caches, cassettes and the layers around `common`.
+#[derive(Clone)]
+pub struct Download {
+    /// Resumes from a partial file of an earlier download.
+    pub resume: bool,
+    /// The expected SHA-256 of the file as hex, verified once complete.
+    pub sha256: Option<String>,
+    on_progress: Option<Callback>,
+    progress: Arc<watch::Sender<Progress>>,
+}
+
+type Callback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+impl Default for Download {
+    fn default() -> Self {
+        Self {
+            resume: true,
+            sha256: None,
+            on_progress: None,
+            progress: Arc::new(watch::Sender::new(Progress::default())),
+        }
+    }
+}
+
+impl Download {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_resume(&mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self.to_owned()
+    }
+    pub fn set_sha256(&mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into());
+        self.to_owned()
+    }
+    /// Called after every chunk written.
+    pub fn on_progress(&mut self, callback: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self.to_owned()
+    }
+
+    /// Follows the progress of downloads with this configuration, clones share it.
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<Progress> {
+        self.progress.subscribe()
+    }
+
+    /// Downloads `path` to `file`, returning its size.
+    pub(crate) async fn run<C: Open>(
+        &self,
+        client: &C,
+        path: &str,
+        file: &Path,
+    ) -> Result<u64, C::Error> {
+        let part = suffixed(file, ".part");
+        let validator = suffixed(file, ".part.validator");
+
+        let mut resume = None;
+        if self.resume {
+            if let (Ok(metadata), Ok(validator)) = (
+                fs::metadata(&part).await,
+                fs::read_to_string(&validator).await,
+            ) {
+                resume = Some((metadata.len(), validator)).filter(|(offset, _)| *offset > 0);
+            }
+        }
+
+        let (head, mut response) = loop {
+            let (head, response) = client
+                .open(
+                    path,
+                    resume.as_ref().map(|(offset, tag)| (*offset, tag.as_str())),
+                )
+                .await?;
+            // the partial file is complete or longer than the file now, start over
+            if head.status == 416 {
+                resume = None;
+                continue;
+            }
+            break (head, response);
+        };
+
+        // the server ignores the range or the file changed
+        let offset = match (head.status, resume) {
+            (206, Some((offset, _))) if head.start == Some(offset) => offset,
+            (206, _) => return Err(Mismatch::Range.into()),
+            _ => 0,
+        };
+        match &head.validator {
+            Some(tag) => fs::write(&validator, tag).await?,
+            None => remove(&validator).await?,
+        }
+        let mut output = if offset > 0 {
+            fs::OpenOptions::new().append(true).open(&part).await?
+        } else {
+            fs::File::create(&part).await?
+        };
+
+        let mut progress = Progress {
+            downloaded: offset,
+            total: head.length.map(|length| offset + length).or(head.total),
+            resumed_from: offset,
+        };
+        self.report(&progress);
+        while let Some(chunk) = C::chunk(&mut response).await {
+            let chunk = chunk?;
+            output.write_all(&chunk).await?;
+            progress.downloaded += chunk.len() as u64;
+            self.report(&progress);
+        }
+        output.flush().await?;
+        drop(output);
+
+        if let Some(expected) = progress.total.filter(|total| *total != progress.downloaded) {
+            // a short file is resumed by the next download
+            if progress.downloaded > expected {
+                remove(&part).await?;
+                remove(&validator).await?;
+            }
+            return Err(Mismatch::Length {
+                expected,
+                received: progress.downloaded,
+            }
+            .into());
+        }
+        if let Some(expected) = &self.sha256 {
+            let computed = sha256(&part).await?;
+            if !computed.eq_ignore_ascii_case(expected) {
+                remove(&part).await?;
+                remove(&validator).await?;
+                return Err(Mismatch::Checksum {
+                    expected: expected.clone(),
+                    computed,
+                }
+                .into());
+            }
+        }
+
+        fs::rename(&part, file).await?;
+        remove(&validator).await?;
+        Ok(progress.downloaded)
+    }
+
+    fn report(&self, progress: &Progress) {
+        self.progress.send_replace(*progress);
+        if let Some(callback) = &self.on_progress {
+            callback(progress);
+        }
+    }
+}
+
+impl Debug for Download {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Download")
+            .field("resume", &self.resume)
+            .field("sha256", &self.sha256)
+            .finish_non_exhaustive()
+    }
+}
+
+fn suffixed(file: &Path, suffix: &str) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+async fn remove(file: &Path) -> io::Result<()> {
+    match fs::remove_file(file).await {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// The SHA-256 of `file` as lowercase hex.
+async fn sha256(file: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(file).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::download::Head;
+
+    #[test]
+    fn download_head() {
+        let headers = [
+            ("content-length", "6"),
+            ("content-range", "bytes 4-9/10"),
+            ("etag", "W/\"weak\""),
+            ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+        ];
+        let head = Head::new(206, |name| {
+            headers
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| *value)
+        });
+        assert_eq!(
+            head,
+            Head {
+                status: 206,
+                length: Some(6),
+                start: Some(4),
+                total: Some(10),
+                // weak tags can not be used for ranges
+                validator: Some(String::from("Wed, 21 Oct 2015 07:28:00 GMT")),
+            }
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use crate::{
+    dns::Dns,
+    engines::hyper::{Hyper, Result},
+    proxy::Proxy,
+};
+use hyper::{
+    client::connect::{dns::Name, Connected, Connection},
+    client::HttpConnector,
+    service::Service,
+    Uri,
+};
+use std::{
+    fmt::Write as _,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    net::TcpStream,
+};
+use url::Url;
+
+/// The TLS backend is chosen through the `hyper-rustls` and `hyper-native-tls` features,
+/// rustls is used when both are enabled.
+#[cfg(feature = "hyper-rustls")]
+pub type Connector = hyper_rustls::HttpsConnector<Transport>;
+#[cfg(all(feature = "hyper-native-tls", not(feature = "hyper-rustls")))]
+pub type Connector = hyper_tls::HttpsConnector<Transport>;
+
+impl Hyper {
+    pub(crate) fn connector(&self) -> Result<Connector> {
+        self.connector_with(false)
+    }
+
+    /// A `WebSocket` is upgraded from HTTP/1.1 and tunnels through HTTP proxies also without TLS.
+    pub(crate) fn connector_with(&self, websocket: bool) -> Result<Connector> {
+        let mut http = HttpConnector::new_with_resolver(Resolver(self.dns.clone()));
+        http.enforce_http(false);
+        http.set_happy_eyeballs_timeout(self.dns.happy_eyeballs_timeout);
+        http.set_keepalive(self.tcp.keepalive);
+        http.set_send_buffer_size(self.tcp.send_buffer_size);
+        http.set_recv_buffer_size(self.tcp.recv_buffer_size);
+        if let Some(nodelay) = self.tcp.nodelay {
+            http.set_nodelay(nodelay);
+        }
+        match self.tcp.local_addresses()? {
+            (Some(v4), Some(v6)) => http.set_local_addresses(v4, v6),
+            (v4, v6) => http.set_local_address(v4.map(IpAddr::V4).or_else(|| v6.map(IpAddr::V6))),
+        }
+        let transport = Transport {
+            http,
+            proxy: self.proxy.clone(),
+            tunnel: websocket,
+            #[cfg(feature = "socks")]
+            dns: self.dns.clone(),
+            #[cfg(unix)]
+            unix_socket: self.unix_socket.clone(),
+        };
+
+        #[cfg(feature = "hyper-rustls")]
+        {
+            let builder = hyper_rustls::HttpsConnectorBuilder::new()
+                .with_tls_config(self.tls.rustls_config()?)
+                .https_or_http();
+            Ok(if websocket {
+                builder.enable_http1().wrap_connector(transport)
+            } else if self.http2.only {
+                builder.enable_http2().wrap_connector(transport)
+            } else {
+                builder
+                    .enable_http1()
+                    .enable_http2()
+                    .wrap_connector(transport)
+            })
+        }
+
+        #[cfg(all(feature = "hyper-native-tls", not(feature = "hyper-rustls")))]
+        {
+            let mut tls = self.tls.native_tls()?;
+            if websocket {
+                tls.request_alpns(&["http/1.1"]);
+            } else if self.http2.only {
+                tls.request_alpns(&["h2"]);
+            } else {
+                tls.request_alpns(&["h2", "http/1.1"]);
+            }
+            Ok(hyper_tls::HttpsConnector::from((
+                transport,
+                tls.build()?.into(),
+            )))
+        }
+    }
+}
+
+/// Opens the connections TLS is layered on, through the proxy configured for the destination.
+#[derive(Debug, Clone)]
+pub struct Transport {
+    http: HttpConnector<Resolver>,
+    proxy: Option<Proxy>,
+    /// Tunnels through HTTP proxies also for plain `http://`.
+    tunnel: bool,
+    /// Resolves targets of `socks5://` proxies.
+    #[cfg(feature = "socks")]
+    dns: Dns,
+    #[cfg(unix)]
+    unix_socket: Option<std::path::PathBuf>,
+}
+
+/// Resolves through the [`Dns`] settings of the client.
+#[derive(Debug, Clone)]
+pub struct Resolver(Dns);
+
+/// A connection opened by [`Transport`].
+#[derive(Debug)]
+pub struct Stream {
+    io: Io,
+    /// Requests are sent to an HTTP proxy in absolute form instead of through a tunnel.
+    forward: bool,
+}
+
+#[derive(Debug)]
+enum Io {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+trait ReadWrite: AsyncRead + AsyncWrite + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Unpin> ReadWrite for T {}
+
+impl Transport {
+    async fn connect(mut self, destination: Uri) -> io::Result<Stream> {
+        #[cfg(unix)]
+        if let Some(path) = &self.unix_socket {
+            return Ok(Stream {
+                io: Io::Unix(tokio::net::UnixStream::connect(path).await?),
+                forward: false,
+            });
+        }
+
+        let host = destination
+            .host()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing host"))?
+            .trim_matches(['[', ']'])
+            .to_owned();
+        let https = destination.scheme_str() == Some("https");
+        let port = destination
+            .port_u16()
+            .unwrap_or(if https { 443 } else { 80 });
+
+        let proxy = self.proxy.as_ref().and_then(|proxy| {
+            Url::parse(&destination.to_string())
+                .ok()
+                .and_then(|url| proxy.for_url(&url).cloned())
+        });
+        let Some(proxy) = proxy else {
+            return Ok(Stream {
+                io: Io::Tcp(self.tcp(destination).await?),
+                forward: false,
+            });
+        };
+
+        let proxy_port = proxy.port_or_known_default().unwrap_or(1080);
+        let proxy_host = proxy.host_str().unwrap_or_default();
+        let tcp = self
+            .tcp(
+                format!("http://{proxy_host}:{proxy_port}")
+                    .parse()
+                    .map_err(io::Error::other)?,
+            )
+            .await?;
+
+        match proxy.scheme() {
+            "http" if https || self.tunnel => Ok(Stream {
+                io: Io::Tcp(tunnel(tcp, &host, port, &proxy).await?),
+                forward: false,
+            }),
+            "http" => Ok(Stream {
+                io: Io::Tcp(tcp),
+                forward: true,
+            }),
+            #[cfg(feature = "socks")]
+            scheme @ ("socks5" | "socks5h") => Ok(Stream {
+                io: Io::Tcp(
+                    socks5(
+                        tcp,
+                        &host,
+                        port,
+                        &proxy,
+                        (scheme == "socks5").then_some(&self.dns),
+                    )
+                    .await?,
+                ),
+                forward: false,
+            }),
+            scheme => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("unsupported proxy scheme {scheme}"),
+            )),
+        }
+    }
+
+    async fn tcp(&mut self, destination: Uri) -> io::Result<TcpStream> {
+        self.http.call(destination).await.map_err(io::Error::other)
+    }
+}
+
+/// Asks an HTTP proxy for a `CONNECT` tunnel to `host:port`.
+async fn tunnel(mut tcp: TcpStream, host: &str, port: u16, proxy: &Url) -> io::Result<TcpStream> {
+    let authority = if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some(authorization) = crate::proxy::authorization(proxy) {
+        // writing to a `String` does not fail
+        let _ = write!(request, "Proxy-Authorization: {authorization}\r\n");
+    }
+    request.push_str("\r\n");
+    tcp.write_all(request.as_bytes()).await?;
+
+    // the proxy sends nothing past the head until the tunnel is used
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > 8192 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "proxy response head too large",
+            ));
+        }
+        head.push(tcp.read_u8().await?);
+    }
+
+    let status = head
+        .split(|byte| *byte == b' ')
+        .nth(1)
+        .and_then(|status| std::str::from_utf8(status).ok())
+        .and_then(|status| status.parse::<u16>().ok());
+    match status {
+        Some(200..=299) => Ok(tcp),
+        Some(status) => Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("proxy refused the tunnel with status {status}"),
+        )),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid proxy response",
+        )),
+    }
+}
+
+#[cfg(feature = "socks")]
+async fn socks5(
+    tcp: TcpStream,
+    host: &str,
+    port: u16,
+    proxy: &Url,
+    local_dns: Option<&Dns>,
+) -> io::Result<TcpStream> {
+    use tokio_socks::{tcp::Socks5Stream, TargetAddr};
+
+    let target = match local_dns {
+        Some(dns) => {
+            let mut address = dns.lookup(host).await?[0];
+            address.set_port(port);
+            TargetAddr::Ip(address)
+        }
+        None => TargetAddr::Domain(host.into(), port),
+    };
+    let decode = |value| percent_encoding::percent_decode_str(value).decode_utf8_lossy();
+    let stream = if proxy.username().is_empty() {
+        Socks5Stream::connect_with_socket(tcp, target).await
+    } else {
+        Socks5Stream::connect_with_password_and_socket(
+            tcp,
+            target,
+            &decode(proxy.username()),
+            &decode(proxy.password().unwrap_or_default()),
+        )
+        .await
+    };
+    Ok(stream.map_err(io::Error::other)?.into_inner())
+}
+
+impl Service<Name> for Resolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let dns = self.0.clone();
+        Box::pin(async move { Ok(dns.lookup(name.as_str()).await?.into_iter()) })
+    }
+}
+
+impl Service<Uri> for Transport {
+    type Response = Stream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Stream>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.http.poll_ready(cx).map_err(io::Error::other)
+    }
+
+    fn call(&mut self, destination: Uri) -> Self::Future {
+        Box::pin(self.clone().connect(destination))
+    }
+}
+
+impl Stream {
+    fn io(&mut self) -> Pin<&mut dyn ReadWrite> {
+        match &mut self.io {
+            Io::Tcp(tcp) => Pin::new(tcp),
+            #[cfg(unix)]
+            Io::Unix(unix) => Pin::new(unix),
+        }
+    }
+}
+
+impl Connection for Stream {
+    fn connected(&self) -> Connected {
+        match &self.io {
+            Io::Tcp(tcp) => tcp.connected().proxy(self.forward),
+            #[cfg(unix)]
+            Io::Unix(_) => Connected::new(),
+        }
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.io().poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.io().poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.io().poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.io().poll_shutdown(cx)
+    }
+}
@@ -1,17 +1,27 @@
 use crate::{
+    dns::Dns,
     engines::hyper::Error,
     engines::hyper::Result,
-    engines::hyper::{Body, Hyper},
+    engines::hyper::{connector::Connector, Body, Hyper},
+    http2::Http2,
+    proxy::Proxy,
+    redirect::{self, Action, Attempt, Policy, TooManyRedirects},
+    tcp::Tcp,
 };
 use hyper::{
     header::HeaderValue,
-    header::{CONTENT_TYPE, USER_AGENT},
+    header::{
+        AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, LOCATION, PROXY_AUTHORIZATION,
+        USER_AGENT,
+    },
     http::HeaderName,
     HeaderMap, Method, Request,
 };
-use hyper_tls::HttpsConnector;
 use std::str::FromStr;
 
+#[cfg(any(feature = "sse", feature = "download"))]
+use crate::engines::Held;
+
 impl Hyper {
     pub fn set_url(&mut self, url: impl Into<String>) -> Self {
         self.url = url.into();
@@ -36,6 +46,128 @@ impl Hyper {
         self.headers = headers;
         self.to_owned()
     }
+    pub fn set_redirect_policy(&mut self, policy: Policy) -> Self {
+        self.redirect = policy;
+        self.to_owned()
+    }
+    pub fn set_http2(&mut self, http2: Http2) -> Self {
+        self.http2 = http2;
+        self.to_owned()
+    }
+    pub fn set_dns(&mut self, dns: Dns) -> Self {
+        self.dns = dns;
+        self.to_owned()
+    }
+    pub fn set_tcp(&mut self, tcp: Tcp) -> Self {
+        self.tcp = tcp;
+        self.to_owned()
+    }
+    pub fn set_proxy(&mut self, proxy: impl Into<Option<Proxy>>) -> Self {
+        self.proxy = proxy.into();
+        self.to_owned()
+    }
+    /// Sends every request over a Unix domain socket, such as `/var/run/docker.sock`.
+    ///
+    /// Without a URL set, paths are requested on `http://localhost`.
+    #[cfg(unix)]
+    pub fn set_unix_socket(&mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self.to_owned()
+    }
+    #[cfg(feature = "__tls")]
+    pub fn set_tls(&mut self, tls: crate::tls::Tls) -> Self {
+        self.tls = tls;
+        self.to_owned()
+    }
+    /// Decompression is enabled by default, disable it to receive the raw compressed bytes.
+    #[cfg(feature = "__compression")]
+    pub fn set_decompress(&mut self, decompress: bool) -> Self {
+        self.no_decompress = !decompress;
+        self.to_owned()
+    }
+    /// The server must accept the encoding, for a single request set it on a clone.
+    #[cfg(feature = "__compression")]
+    pub fn set_body_encoding(
+        &mut self,
+        encoding: impl Into<Option<crate::compression::Encoding>>,
+    ) -> Self {
+        self.body_encoding = encoding.into();
+        self.to_owned()
+    }
+    /// Sends and stores cookies through `jar`, clones of the jar share their cookies.
+    #[cfg(feature = "cookies")]
+    pub fn set_cookie_jar(&mut self, jar: crate::cookies::Jar) -> Self {
+        self.cookies = Some(jar);
+        self.to_owned()
+    }
+    /// Records every request to a cassette, or answers them from one without a network.
+    #[cfg(feature = "vcr")]
+    pub fn set_vcr(&mut self, vcr: impl Into<Option<crate::vcr::Vcr>>) -> Self {
+        self.vcr = vcr.into();
+        self.to_owned()
+    }
+    /// Caches responses in `cache`, clones of the cache share their responses.
+    #[cfg(feature = "cache")]
+    pub fn set_cache(&mut self, cache: impl Into<Option<crate::cache::Cache>>) -> Self {
+        self.cache = cache.into();
+        self.to_owned()
+    }
+    /// Shares the response of a `GET` without a body with identical requests in flight.
+    #[cfg(feature = "single-flight")]
+    pub fn set_single_flight(
+        &mut self,
+        single_flight: impl Into<Option<crate::single_flight::SingleFlight<Body>>>,
+    ) -> Self {
+        self.single_flight = single_flight.into();
+        self.to_owned()
+    }
+    /// Queues requests beyond the limits of `limit`, clones of the limit share their slots.
+    #[cfg(feature = "limit")]
+    pub fn set_limit(&mut self, limit: impl Into<Option<crate::limit::Limit>>) -> Self {
+        self.limit = limit.into();
+        self.to_owned()
+    }
+    /// Fails requests to hosts that keep failing with `CircuitOpen`, clones of the breaker
+    /// share their circuits.
+    #[cfg(feature = "circuit-breaker")]
+    pub fn set_circuit_breaker(
+        &mut self,
+        breaker: impl Into<Option<crate::breaker::Breaker>>,
+    ) -> Self {
+        self.breaker = breaker.into();
+        self.to_owned()
+    }
+}
+
+#[cfg(feature = "cache")]
+impl From<crate::cache::Entry> for Body {
+    fn from(entry: crate::cache::Entry) -> Self {
+        Self {
+            status: entry.status(),
+            headers: entry.headers(),
+            url: entry.url,
+            history: Vec::new(),
+            version: hyper::Version::HTTP_11,
+            body: Some(entry.body),
+        }
+    }
+}
+
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+impl crate::observe::Observed for Body {
+    fn status(&self) -> u16 {
+        self.status.as_u16()
+    }
+    #[cfg(feature = "tracing")]
+    fn protocol_version(&self) -> Option<&'static str> {
+        crate::observe::protocol_version(self.version)
+    }
+    fn body_size(&self) -> usize {
+        self.body.as_ref().map_or(0, Vec::len)
+    }
+    fn redirect_count(&self) -> usize {
+        self.history.len()
+    }
 }
 
 // methods
@@ -62,17 +194,267 @@ impl Hyper {
     pub fn new() -> Self {
         Self::default()
     }
+    fn client(&self) -> Result<hyper::Client<Connector>> {
+        let mut builder = hyper::Client::builder();
+        builder
+            .http2_only(self.http2.only)
+            .http2_adaptive_window(self.http2.adaptive_window)
+            .http2_initial_stream_window_size(self.http2.initial_stream_window_size)
+            .http2_initial_connection_window_size(self.http2.initial_connection_window_size)
+            .http2_keep_alive_interval(self.http2.keep_alive_interval);
+
+        if let Some(timeout) = self.http2.keep_alive_timeout {
+            builder.http2_keep_alive_timeout(timeout);
+        }
+        Ok(builder.build(self.connector()?))
+    }
+
     pub async fn common(
         &self,
         method: Method,
         path: String,
         data: Option<Vec<u8>>,
     ) -> Result<Body> {
-        // http request
-        let uri = format!("{}{path}", self.url);
-        let mut request = Request::builder().method(method).uri(uri);
+        #[cfg(feature = "tracing")]
+        let span = crate::trace::span(
+            "hyper",
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            data.as_ref().map_or(0, Vec::len),
+        );
+        #[cfg(feature = "metrics")]
+        let meter = crate::meter::Meter::new(
+            "hyper",
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            data.as_ref().map_or(0, Vec::len),
+        );
+
+        #[cfg(feature = "circuit-breaker")]
+        let url = format!("{}{path}", self.url);
 
-        let client = hyper::Client::builder().build::<_, hyper::Body>(HttpsConnector::new());
+        // boxed, every layer below would otherwise hold a copy of the request future
+        let response = Box::pin(async {
+            #[cfg(feature = "single-flight")]
+            if let Some(flight) = self
+                .single_flight
+                .as_ref()
+                .filter(|_| method == Method::GET && data.is_none())
+            {
+                let key = crate::single_flight::key(
+                    method.as_str(),
+                    &format!("{}{path}", self.url),
+                    &self.request_headers()?,
+                );
+                return flight.run(key, self.dispatch(method, path, data)).await;
+            }
+            self.dispatch(method, path, data).await
+        });
+
+        #[cfg(feature = "circuit-breaker")]
+        let response = crate::breaker::Breaker::call(
+            self.breaker.as_ref(),
+            &url,
+            response,
+            |body| body.status.as_u16(),
+            Error::is_failure,
+        );
+        #[cfg(feature = "metrics")]
+        let response = meter.measure(response);
+        #[cfg(feature = "tracing")]
+        return crate::trace::instrument(span, response).await;
+        #[cfg(not(feature = "tracing"))]
+        response.await
+    }
+
+    async fn dispatch(&self, method: Method, path: String, data: Option<Vec<u8>>) -> Result<Body> {
+        #[cfg(feature = "vcr")]
+        if let Some(vcr) = &self.vcr {
+            return self.cassette(vcr, method, path, data).await;
+        }
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &self.cache {
+            return self.cached(cache, method, path, data).await;
+        }
+        self.send(method, path, data).await
+    }
+
+    /// The headers sent with every request, as seen by the cassette and single-flight keys.
+    #[cfg(any(feature = "vcr", feature = "single-flight"))]
+    fn request_headers(&self) -> Result<hyper::HeaderMap> {
+        let mut headers = self.headers.clone();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(crate::USER_AGENT))?,
+        );
+        if let Some(content_type) = self.content_type.as_deref() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
+        }
+        Ok(headers)
+    }
+
+    #[cfg(feature = "cache")]
+    async fn cached(
+        &self,
+        cache: &crate::cache::Cache,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<Body> {
+        let url = format!("{}{path}", self.url);
+        let (stale, body) = match cache.lookup(method.as_str(), &url, &self.headers) {
+            crate::cache::Lookup::Fresh(entry) => return Ok(entry.into()),
+            crate::cache::Lookup::Stale(entry, validators) => {
+                let mut client = self.clone();
+                client.headers.extend(validators);
+                (Some(entry), client.send(method.clone(), path, data).await?)
+            }
+            crate::cache::Lookup::Miss => (None, self.send(method.clone(), path, data).await?),
+        };
+
+        if let Some(entry) = stale.filter(|_| body.status == hyper::StatusCode::NOT_MODIFIED) {
+            return Ok(cache
+                .revalidated(method.as_str(), &url, entry, &body.headers)
+                .into());
+        }
+        cache.store(
+            method.as_str(),
+            &url,
+            &self.headers,
+            body.status,
+            body.url.clone(),
+            &body.headers,
+            body.body.as_deref().unwrap_or_default(),
+        );
+        Ok(body)
+    }
+
+    #[cfg(feature = "vcr")]
+    async fn cassette(
+        &self,
+        vcr: &crate::vcr::Vcr,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<Body> {
+        let request = vcr.request(
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            &self.request_headers()?,
+            data.as_deref(),
+        );
+
+        match vcr.mode {
+            crate::vcr::Mode::Replay => {
+                let response = vcr.replay_response(&request)?;
+                Ok(Body {
+                    body: response.body.bytes()?,
+                    status: response.status()?,
+                    headers: response.headers(),
+                    url: response.url,
+                    history: Vec::new(),
+                    version: hyper::Version::HTTP_11,
+                })
+            }
+            crate::vcr::Mode::Record => {
+                let body = self.send(method, path, data).await?;
+                #[cfg(feature = "__compression")]
+                let decoded = !self.no_decompress;
+                #[cfg(not(feature = "__compression"))]
+                let decoded = false;
+                vcr.record_response(
+                    request,
+                    body.status,
+                    &body.url,
+                    &body.headers,
+                    body.body.as_deref(),
+                    decoded,
+                )?;
+                Ok(body)
+            }
+        }
+    }
+
+    async fn send(&self, method: Method, path: String, data: Option<Vec<u8>>) -> Result<Body> {
+        let url = self.url_for(&path)?;
+        // held until the response body is read, across redirects
+        let _slot = self.slot(url.as_str()).await?;
+        #[cfg(feature = "__compression")]
+        let sent = method.clone();
+        let (response, url, history) = self.open(method, url, data).await?;
+        let status = response.status();
+        let version = response.version();
+        let response_headers = response.headers().clone();
+
+        #[cfg(feature = "__compression")]
+        let content_encoding = response
+            .headers()
+            .get(hyper::header::CONTENT_ENCODING)
+            .and_then(|encoding| encoding.to_str().ok())
+            .filter(|_| {
+                !self.no_decompress && crate::compression::has_body(sent.as_str(), status.as_u16())
+            })
+            .map(ToOwned::to_owned);
+
+        let body = hyper::body::to_bytes(response.into_body()).await?.to_vec();
+
+        #[cfg(feature = "__compression")]
+        let body = match content_encoding {
+            Some(encoding) => crate::compression::decode(&encoding, body)?,
+            None => body,
+        };
+
+        Ok(Body {
+            status,
+            headers: response_headers,
+            version,
+            url: url.into(),
+            history,
+            body: body.into(),
+        })
+    }
+
+    /// Waits for the `limit` slots of a request to `url`.
+    #[cfg(feature = "limit")]
+    pub(crate) async fn slot(&self, url: &str) -> Result<crate::engines::Slot> {
+        match &self.limit {
+            Some(limit) => Ok(Some(limit.acquire(url).await?)),
+            None => Ok(None),
+        }
+    }
+    #[cfg(not(feature = "limit"))]
+    #[allow(clippy::unused_async, clippy::unnecessary_wraps)]
+    pub(crate) async fn slot(&self, _url: &str) -> Result<crate::engines::Slot> {
+        Ok(None)
+    }
+
+    /// The URL of `path`, requested on `http://localhost` over a Unix socket without a URL set.
+    pub(crate) fn url_for(&self, path: &str) -> Result<url::Url> {
+        #[cfg(unix)]
+        let base = match self.unix_socket {
+            Some(_) if self.url.is_empty() && path.starts_with('/') => "http://localhost",
+            _ => &self.url,
+        };
+        #[cfg(not(unix))]
+        let base = &self.url;
+
+        Ok(url::Url::parse(&format!("{base}{path}"))?)
+    }
+
+    /// Sends a request and follows its redirects, returning the final response with its body
+    /// unread, its URL and the URLs that redirected to it.
+    async fn open(
+        &self,
+        method: Method,
+        url: url::Url,
+        data: Option<Vec<u8>>,
+    ) -> Result<(hyper::Response<hyper::Body>, url::Url, Vec<String>)> {
+        let mut url = url;
+        let mut method = method;
+        let mut data = data;
+        let mut history = Vec::new();
+
+        let client = self.client()?;
 
         // headers
         let mut headers = self.headers.clone();
@@ -85,28 +467,117 @@ impl Hyper {
             headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
         };
 
-        match request.headers_mut() {
-            Some(original_headers) => original_headers.extend(headers),
-            None => {
-                for (key, value) in headers {
-                    if let Some(key) = key {
-                        request = request.header(key, value);
+        #[cfg(feature = "__compression")]
+        headers
+            .entry(hyper::header::ACCEPT_ENCODING)
+            .or_insert(HeaderValue::from_str(
+                &crate::compression::accept_encoding(),
+            )?);
+
+        #[cfg(feature = "__compression")]
+        if let (Some(encoding), Some(body)) = (self.body_encoding, &data) {
+            data = Some(encoding.encode(body)?);
+            headers.insert(
+                hyper::header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+        }
+
+        loop {
+            let response = self
+                .send_once(&client, &method, &url, headers.clone(), data.clone())
+                .await?;
+
+            // redirects
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| url.join(location).ok())
+                .filter(|_| status.is_redirection());
+
+            if let Some(next) = location {
+                history.push(url.to_string());
+                match self.redirect.action(&Attempt {
+                    status: status.as_u16(),
+                    next: next.as_str(),
+                    previous: &history,
+                }) {
+                    Action::Follow => {
+                        if redirect::switches_to_get(status.as_u16(), method.as_str()) {
+                            method = Method::GET;
+                            data = None;
+                            headers.remove(CONTENT_TYPE);
+                            headers.remove(CONTENT_LENGTH);
+                            headers.remove(hyper::header::CONTENT_ENCODING);
+                        }
+                        if redirect::is_cross_origin(&url, &next) {
+                            for header in [AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION] {
+                                headers.remove(header);
+                            }
+                        }
+                        url = next;
+                        continue;
+                    }
+                    Action::Stop => {
+                        history.pop();
                     }
+                    Action::Error => return Err(Error::Redirect(TooManyRedirects)),
                 }
             }
+
+            return Ok((response, url, history));
         }
-        // request
-        let response = client
-            .request(request.body(data.map_or_else(hyper::Body::empty, hyper::Body::from))?)
-            .await?;
+    }
 
-        Ok(Body {
-            status: response.status(),
-            body: hyper::body::to_bytes(response.into_body())
-                .await?
-                .to_vec()
-                .into(),
-        })
+    /// Sends a single request of a redirect chain with the cookies and proxy credentials for
+    /// `url`, storing the cookies of its response.
+    async fn send_once(
+        &self,
+        client: &hyper::Client<Connector>,
+        method: &Method,
+        url: &url::Url,
+        mut headers: HeaderMap,
+        data: Option<Vec<u8>>,
+    ) -> Result<hyper::Response<hyper::Body>> {
+        #[cfg(feature = "cookies")]
+        if let Some(cookie) = self.cookies.as_ref().and_then(|jar| jar.cookie_header(url)) {
+            headers
+                .entry(hyper::header::COOKIE)
+                .or_insert(HeaderValue::from_str(&cookie)?);
+        }
+
+        // requests forwarded by an HTTP proxy carry its credentials, tunnels send them on CONNECT
+        if let Some(authorization) = self
+            .proxy
+            .as_ref()
+            .and_then(|proxy| proxy.for_url(url))
+            .filter(|proxy| url.scheme() == "http" && proxy.scheme() == "http")
+            .and_then(crate::proxy::authorization)
+        {
+            headers.insert(PROXY_AUTHORIZATION, HeaderValue::from_str(&authorization)?);
+        }
+
+        let mut request = Request::builder()
+            .method(method.clone())
+            .uri(url.as_str())
+            .body(data.map_or_else(hyper::Body::empty, hyper::Body::from))?;
+        *request.headers_mut() = headers;
+        let response = client.request(request).await?;
+
+        #[cfg(feature = "cookies")]
+        if let Some(jar) = &self.cookies {
+            jar.store_response_cookies(
+                url,
+                response
+                    .headers()
+                    .get_all(hyper::header::SET_COOKIE)
+                    .iter()
+                    .filter_map(|value| value.to_str().ok()),
+            );
+        }
+        Ok(response)
     }
 
     pub async fn request_raw(
@@ -132,3 +603,199 @@ impl Hyper {
             .serde_switch()
     }
 }
+
+#[cfg(feature = "sse")]
+impl Hyper {
+    /// Opens `path` as a `text/event-stream` once the first event is awaited.
+    #[must_use]
+    pub fn event_source(&self, path: impl Into<String>) -> crate::sse::EventSource<Self> {
+        crate::sse::EventSource::new(self.clone(), path.into())
+    }
+}
+
+#[cfg(feature = "sse")]
+impl crate::sse::Connect for Hyper {
+    type Response = Held<hyper::Body>;
+    type Error = Error;
+
+    async fn connect(
+        &self,
+        path: &str,
+        last_event_id: Option<&str>,
+    ) -> Result<Option<Held<hyper::Body>>> {
+        let mut client = self.clone();
+        client.headers.insert(
+            hyper::header::ACCEPT,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        client.headers.insert(
+            hyper::header::CACHE_CONTROL,
+            HeaderValue::from_static("no-cache"),
+        );
+        // compressed bodies are only decoded once complete
+        client.headers.insert(
+            hyper::header::ACCEPT_ENCODING,
+            HeaderValue::from_static("identity"),
+        );
+        if let Some(id) = last_event_id {
+            client
+                .headers
+                .insert("last-event-id", HeaderValue::from_str(id)?);
+        }
+
+        let url = client.url_for(path)?;
+        let slot = client.slot(url.as_str()).await?;
+        let (response, _, _) = client.open(Method::GET, url, None).await?;
+        match response.status() {
+            hyper::StatusCode::OK => Ok(Some(Held::new(response.into_body(), slot))),
+            hyper::StatusCode::NO_CONTENT => Ok(None),
+            status => Err(Error::StatusCode(status)),
+        }
+    }
+
+    async fn chunk(response: &mut Held<hyper::Body>) -> Option<Vec<u8>> {
+        hyper::body::HttpBody::data(&mut **response)
+            .await?
+            .ok()
+            .map(|chunk| chunk.to_vec())
+    }
+}
+
+#[cfg(feature = "poll")]
+impl Hyper {
+    /// Polls `path` with `GET` once the first body is awaited.
+    #[must_use]
+    pub fn poll(&self, path: impl Into<String>) -> crate::poll::Poller<Self> {
+        crate::poll::Poller::new(self.clone(), path.into())
+    }
+}
+
+#[cfg(feature = "poll")]
+impl crate::poll::Fetch for Hyper {
+    type Error = Error;
+
+    async fn fetch(&self, path: &str, etag: Option<&str>) -> Result<Option<crate::poll::Fetched>> {
+        let mut client = self.clone();
+        if let Some(etag) = etag {
+            client
+                .headers
+                .insert(hyper::header::IF_NONE_MATCH, HeaderValue::from_str(etag)?);
+        }
+
+        let response = client.common(Method::GET, path.to_owned(), None).await?;
+        match response.status {
+            hyper::StatusCode::OK => Ok(Some(crate::poll::Fetched {
+                etag: response
+                    .headers
+                    .get(hyper::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_owned),
+                body: response.body.unwrap_or_default(),
+            })),
+            hyper::StatusCode::NOT_MODIFIED => Ok(None),
+            status => Err(Error::StatusCode(status)),
+        }
+    }
+}
+
+#[cfg(feature = "paginate")]
+impl Hyper {
+    /// Pages through `path` with `GET` once the first item is awaited.
+    #[must_use]
+    pub fn paginate<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        next: crate::paginate::Next,
+    ) -> crate::paginate::Paginator<Self, T> {
+        crate::paginate::Paginator::new(self.clone(), path, next)
+    }
+}
+
+#[cfg(feature = "paginate")]
+impl crate::paginate::Get for Hyper {
+    type Error = Error;
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.url)
+    }
+
+    async fn get(&self, url: &str) -> Result<crate::paginate::Response> {
+        // the next pages are absolute URLs
+        let mut client = self.clone();
+        client.url = String::new();
+
+        let response = client.common(Method::GET, url.to_owned(), None).await?;
+        let link = response
+            .headers
+            .get(hyper::header::LINK)
+            .and_then(|link| link.to_str().ok())
+            .map(str::to_owned);
+        Ok(crate::paginate::Response {
+            body: response.bytes()?,
+            link,
+        })
+    }
+}
+
+#[cfg(feature = "download")]
+impl Hyper {
+    /// Streams `path` to `file`, returning the size of the file.
+    pub async fn download_to(
+        &self,
+        path: &str,
+        file: impl AsRef<std::path::Path>,
+        download: &crate::download::Download,
+    ) -> Result<u64> {
+        download.run(self, path, file.as_ref()).await
+    }
+}
+
+#[cfg(feature = "download")]
+impl crate::download::Open for Hyper {
+    type Response = Held<hyper::Body>;
+    type Error = Error;
+
+    async fn open(
+        &self,
+        path: &str,
+        resume: Option<(u64, &str)>,
+    ) -> Result<(crate::download::Head, Held<hyper::Body>)> {
+        let mut client = self.clone();
+        // ranges are counted in bytes of the file as stored
+        client.headers.insert(
+            hyper::header::ACCEPT_ENCODING,
+            HeaderValue::from_static("identity"),
+        );
+        if let Some((offset, validator)) = resume {
+            client.headers.insert(
+                hyper::header::RANGE,
+                HeaderValue::from_str(&format!("bytes={offset}-"))?,
+            );
+            client
+                .headers
+                .insert(hyper::header::IF_RANGE, HeaderValue::from_str(validator)?);
+        }
+
+        let url = client.url_for(path)?;
+        let slot = client.slot(url.as_str()).await?;
+        let (response, _, _) = client.open(Method::GET, url, None).await?;
+        match response.status().as_u16() {
+            200 | 206 => {}
+            416 if resume.is_some() => {}
+            _ => return Err(Error::StatusCode(response.status())),
+        }
+        let head = crate::download::Head::new(response.status().as_u16(), |name| {
+            response.headers().get(name)?.to_str().ok()
+        });
+        Ok((head, Held::new(response.into_body(), slot)))
+    }
+
+    async fn chunk(response: &mut Held<hyper::Body>) -> Option<Result<Vec<u8>>> {
+        Some(
+            hyper::body::HttpBody::data(&mut **response)
+                .await?
+                .map(|chunk| chunk.to_vec())
+                .map_err(Error::from),
+        )
+    }
+}
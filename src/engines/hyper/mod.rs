@@ -1,6 +1,9 @@
+pub mod connector;
 pub mod core;
 pub mod results;
 pub mod tests;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 // re-exports
 use crate::engines::hyper::results::Error;
@@ -9,9 +12,18 @@ pub use hyper::Method;
 use hyper::StatusCode;
 
 // structures
+#[derive(Clone)]
 pub struct Body {
     pub body: Option<Vec<u8>>,
     pub status: StatusCode,
+    /// The headers of the final response.
+    pub headers: HeaderMap,
+    /// The URL of the final response, after following redirects.
+    pub url: String,
+    /// Every URL that redirected before reaching `url`, in order.
+    pub history: Vec<String>,
+    /// The HTTP version negotiated for the final response.
+    pub version: hyper::Version,
 }
 pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Clone, Default)]
@@ -20,10 +32,38 @@ pub struct Hyper {
     pub user_agent: Option<String>,
     pub content_type: Option<String>,
     pub headers: hyper::HeaderMap,
+    pub redirect: crate::redirect::Policy,
+    pub http2: crate::http2::Http2,
+    pub proxy: Option<crate::proxy::Proxy>,
+    pub dns: crate::dns::Dns,
+    pub tcp: crate::tcp::Tcp,
+    /// Connects to this socket instead of the host of the request URL.
+    #[cfg(unix)]
+    pub unix_socket: Option<std::path::PathBuf>,
+    #[cfg(feature = "__tls")]
+    pub tls: crate::tls::Tls,
+    /// Keeps compressed response bodies as received, `Accept-Encoding` is still sent.
+    #[cfg(feature = "__compression")]
+    pub no_decompress: bool,
+    /// Compresses request bodies and sets `Content-Encoding` accordingly.
+    #[cfg(feature = "__compression")]
+    pub body_encoding: Option<crate::compression::Encoding>,
+    #[cfg(feature = "cookies")]
+    pub cookies: Option<crate::cookies::Jar>,
+    #[cfg(feature = "vcr")]
+    pub vcr: Option<crate::vcr::Vcr>,
+    #[cfg(feature = "cache")]
+    pub cache: Option<crate::cache::Cache>,
+    #[cfg(feature = "single-flight")]
+    pub single_flight: Option<crate::single_flight::SingleFlight<Body>>,
+    #[cfg(feature = "limit")]
+    pub limit: Option<crate::limit::Limit>,
+    #[cfg(feature = "circuit-breaker")]
+    pub breaker: Option<crate::breaker::Breaker>,
 }
 
 impl Hyper {
     pub fn header_delete(&mut self, key: impl Into<String>) {
         self.headers.remove(key.into());
     }
-}
\ No newline at end of file
+}
@@ -8,8 +8,32 @@ pub enum Error {
     #[cfg(feature = "serde")]
     Serde(serde_json::Error),
     StatusCode(StatusCode),
+    Io(std::io::Error),
+    #[cfg(feature = "hyper-native-tls")]
+    Tls(native_tls::Error),
     HeaderName(InvalidHeaderName),
     HeaderValue(InvalidHeaderValue),
+    Url(url::ParseError),
+    Redirect(crate::redirect::TooManyRedirects),
+    /// Replay found no recorded exchange for the request.
+    #[cfg(feature = "vcr")]
+    Vcr(crate::vcr::Unmatched),
+    /// Too many requests were waiting for a slot of the [`Limit`](crate::limit::Limit).
+    #[cfg(feature = "limit")]
+    QueueFull(crate::limit::QueueFull),
+    /// The circuit of the host is open after too many failed requests.
+    #[cfg(feature = "circuit-breaker")]
+    CircuitOpen(crate::breaker::CircuitOpen),
+    /// Boxed, the handshake errors hold the whole response.
+    #[cfg(feature = "websocket")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    #[cfg(feature = "msgpack")]
+    MsgPackEncode(rmp_serde::encode::Error),
+    #[cfg(feature = "msgpack")]
+    MsgPackDecode(rmp_serde::decode::Error),
+    /// The downloaded file is not the one expected.
+    #[cfg(feature = "download")]
+    Download(crate::download::Mismatch),
 }
 
 impl From<hyper::Error> for Error {
@@ -31,6 +55,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<url::ParseError> for Error {
+    fn from(value: url::ParseError) -> Self {
+        Self::Url(value)
+    }
+}
+
 impl From<InvalidHeaderName> for Error {
     fn from(value: InvalidHeaderName) -> Self {
         Self::HeaderName(value)
@@ -42,3 +72,78 @@ impl From<InvalidHeaderValue> for Error {
         Self::HeaderValue(value)
     }
 }
+
+#[cfg(feature = "hyper-native-tls")]
+impl From<native_tls::Error> for Error {
+    fn from(value: native_tls::Error) -> Self {
+        Self::Tls(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[cfg(feature = "vcr")]
+impl From<crate::vcr::Unmatched> for Error {
+    fn from(value: crate::vcr::Unmatched) -> Self {
+        Self::Vcr(value)
+    }
+}
+
+#[cfg(feature = "limit")]
+impl From<crate::limit::QueueFull> for Error {
+    fn from(value: crate::limit::QueueFull) -> Self {
+        Self::QueueFull(value)
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+impl From<crate::breaker::CircuitOpen> for Error {
+    fn from(value: crate::breaker::CircuitOpen) -> Self {
+        Self::CircuitOpen(value)
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+impl Error {
+    /// The host could not be reached or answered with a server error, counted by the
+    /// circuit breaker. Other errors are left uncounted.
+    pub(crate) fn is_failure(&self) -> bool {
+        match self {
+            Self::Engine(_) | Self::Io(_) => true,
+            Self::StatusCode(status) => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(value: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(value))
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(value: rmp_serde::encode::Error) -> Self {
+        Self::MsgPackEncode(value)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(value: rmp_serde::decode::Error) -> Self {
+        Self::MsgPackDecode(value)
+    }
+}
+
+#[cfg(feature = "download")]
+impl From<crate::download::Mismatch> for Error {
+    fn from(value: crate::download::Mismatch) -> Self {
+        Self::Download(value)
+    }
+}
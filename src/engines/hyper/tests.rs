@@ -1,21 +1,889 @@
 #[cfg(test)]
 mod tests {
-    use crate::engines::hyper::Hyper;
-    use hyper::Method;
-    use serde_json::Value;
+    use crate::{
+        dns::Dns,
+        engines::hyper::{Error, Hyper},
+        proxy::Proxy,
+        test_util::{respond, Server},
+    };
+    use hyper::{Method, StatusCode};
 
     #[tokio::test]
-    async fn hyper_req_raw() {
-        assert!(Hyper::new()
-            .request_raw(Method::GET, "https://api.revolt.chat", None)
-            .await
-            .is_ok());
+    async fn hyper_req_raw() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/", |_| respond(200, "revolt"));
+
+        assert_eq!(
+            Hyper::new()
+                .set_url(server.url())
+                .request_raw(Method::GET, "/", None)
+                .await?,
+            b"revolt"
+        );
+        assert!(matches!(
+            Hyper::new()
+                .set_url(server.url())
+                .request_raw(Method::GET, "/missing", None)
+                .await,
+            Err(Error::StatusCode(StatusCode::NOT_FOUND))
+        ));
+        Ok(())
     }
+
+    #[cfg(feature = "serde")]
     #[tokio::test]
-    async fn hyper_req() {
+    async fn hyper_req() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::POST, "/empty.json", |request| {
+            respond(200, request.body.clone())
+        });
+
+        assert_eq!(
+            Hyper::new()
+                .set_url(server.url())
+                .set_content_type("application/json")
+                .request::<serde_json::Value>(Method::POST, "/empty.json", b"{}".to_vec())
+                .await?,
+            serde_json::json!({})
+        );
+        let received = server.received();
+        assert_eq!(received[0].headers["content-type"], "application/json");
+        Ok(())
+    }
+
+    /// A redirect with `status` to `location`, `500` when `location` is not a header value.
+    fn redirect(status: u16, location: &str) -> hyper::Response<Vec<u8>> {
+        hyper::Response::builder()
+            .status(status)
+            .header(hyper::header::LOCATION, location)
+            .body(Vec::new())
+            .unwrap_or_else(|_| respond(500, Vec::new()))
+    }
+
+    #[tokio::test]
+    async fn hyper_redirects() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server
+            .route(Method::POST, "/found", |_| redirect(302, "/see-other"))
+            .route(Method::GET, "/see-other", |_| redirect(303, "/users"))
+            .route(Method::GET, "/users", |request| {
+                respond(200, request.method.as_str())
+            })
+            .route(Method::POST, "/temporary", |_| redirect(307, "/echo"))
+            .route(Method::POST, "/echo", |request| {
+                respond(200, request.body.clone())
+            })
+            .route(Method::GET, "/loop", |_| redirect(302, "/loop"));
+        let client = Hyper::new()
+            .set_url(server.url())
+            .set_content_type("application/json");
+
+        // a POST becomes a GET without a body after 302 and stays one after 303
+        assert_eq!(
+            client
+                .request_raw(Method::POST, "/found", b"{}".to_vec())
+                .await?,
+            b"GET"
+        );
+        let received = server.received();
+        assert_eq!(received[1].method, Method::GET);
+        assert!(received[1].body.is_empty());
+        assert!(!received[1].headers.contains_key("content-type"));
+        assert_eq!(received[2].uri, "/users");
+
+        // 307 repeats the request with its body
+        assert_eq!(
+            client
+                .request_raw(Method::POST, "/temporary", b"{}".to_vec())
+                .await?,
+            b"{}"
+        );
+        assert_eq!(
+            server.received()[4].headers["content-type"],
+            "application/json"
+        );
+
+        assert!(matches!(
+            client.request_raw(Method::GET, "/loop", None).await,
+            Err(Error::Redirect(_))
+        ));
+        // the first request and 10 redirects followed
+        assert_eq!(server.received().len(), 5 + 11);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hyper_cross_origin_redirect() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        let other = Server::start().await?;
+        let away = format!("{}/landing", other.url());
+        server
+            .route(Method::GET, "/same", |_| redirect(302, "/users"))
+            .route(Method::GET, "/users", |_| respond(200, "ok"))
+            .route(Method::GET, "/away", move |_| redirect(302, &away));
+        other.route(Method::GET, "/landing", |_| respond(200, "ok"));
+        let client = Hyper::new()
+            .set_url(server.url())
+            .add_header("authorization", "Bearer secret")?
+            .add_header("cookie", "session=abc")?;
+
+        // credentials follow redirects within an origin only
+        client.request_raw(Method::GET, "/same", None).await?;
+        let received = server.received();
+        assert_eq!(received[1].headers["authorization"], "Bearer secret");
+        assert_eq!(received[1].headers["cookie"], "session=abc");
+
+        client.request_raw(Method::GET, "/away", None).await?;
+        let received = other.received();
+        assert_eq!(received[0].uri, "/landing");
+        assert!(!received[0].headers.contains_key("authorization"));
+        assert!(!received[0].headers.contains_key("cookie"));
+        Ok(())
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn hyper_gzip_without_body() -> crate::engines::hyper::Result<()> {
+        let gzip = |status| {
+            let mut response = respond(status, Vec::new());
+            response.headers_mut().insert(
+                hyper::header::CONTENT_ENCODING,
+                hyper::header::HeaderValue::from_static("gzip"),
+            );
+            response
+        };
+        let server = Server::start().await?;
+        server
+            .route(Method::HEAD, "/", move |_| gzip(200))
+            .route(Method::GET, "/empty", move |_| gzip(204))
+            .route(Method::GET, "/cached", move |_| gzip(304));
+        let client = Hyper::new().set_url(server.url());
+
+        // the encoding describes a body that is not sent
+        for (method, path, status) in [
+            (Method::HEAD, "/", 200),
+            (Method::GET, "/empty", 204),
+            (Method::GET, "/cached", 304),
+        ] {
+            let response = client.common(method, String::from(path), None).await?;
+            assert_eq!(response.status.as_u16(), status);
+            assert_eq!(response.body, Some(Vec::new()));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hyper_http_proxy() -> crate::engines::hyper::Result<()> {
+        let proxy = Server::start().await?;
+        proxy.route(Method::GET, "/users", |_| respond(200, "ok"));
+
+        let body = Hyper::new()
+            .set_proxy(Proxy::all(&format!(
+                "http://user:pass@{}",
+                proxy.address()
+            ))?)
+            .request_raw(Method::GET, "http://api.example.invalid/users", None)
+            .await?;
+        let received = proxy.received();
+
+        assert_eq!(body, b"ok");
+        assert_eq!(received[0].uri, "http://api.example.invalid/users");
+        assert_eq!(
+            received[0].headers["proxy-authorization"],
+            "Basic dXNlcjpwYXNz"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hyper_https_proxy_tunnel() -> crate::engines::hyper::Result<()> {
+        // unrouted, so the tunnel is refused with 404
+        let proxy = Server::start().await?;
+
+        let result = Hyper::new()
+            .set_proxy(Proxy::all(&format!(
+                "http://user:pass@{}",
+                proxy.address()
+            ))?)
+            .request_raw(Method::GET, "https://api.example.invalid/users", None)
+            .await;
+        let received = proxy.received();
+
+        assert!(result.is_err());
+        assert_eq!(received[0].method, Method::CONNECT);
+        assert_eq!(received[0].uri, "api.example.invalid:443");
+        assert_eq!(
+            received[0].headers["proxy-authorization"],
+            "Basic dXNlcjpwYXNz"
+        );
+        Ok(())
+    }
+
+    /// A SOCKS5 proxy without authentication that connects every target port on `127.0.0.1`,
+    /// recording the targets as sent by the client.
+    #[cfg(feature = "socks")]
+    async fn socks5_proxy() -> std::io::Result<(
+        std::net::SocketAddr,
+        std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    )> {
+        use std::{
+            io,
+            net::Ipv4Addr,
+            sync::{Arc, Mutex, PoisonError},
+        };
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::{TcpListener, TcpStream},
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let targets: Arc<Mutex<Vec<String>>> = Arc::default();
+        let recorded = targets.clone();
+        tokio::spawn(async move {
+            while let Ok((mut client, _)) = listener.accept().await {
+                let targets = recorded.clone();
+                tokio::spawn(async move {
+                    // version and methods, answered with no authentication
+                    let mut greeting = [0; 2];
+                    client.read_exact(&mut greeting).await?;
+                    client
+                        .read_exact(&mut vec![0; usize::from(greeting[1])])
+                        .await?;
+                    client.write_all(&[5, 0]).await?;
+
+                    // version, CONNECT, reserved and the address type
+                    let mut request = [0; 4];
+                    client.read_exact(&mut request).await?;
+                    let host = match request[3] {
+                        1 => Ipv4Addr::from(client.read_u32().await?).to_string(),
+                        3 => {
+                            let mut domain = vec![0; usize::from(client.read_u8().await?)];
+                            client.read_exact(&mut domain).await?;
+                            String::from_utf8_lossy(&domain).into_owned()
+                        }
+                        _ => return Err(io::Error::from(io::ErrorKind::Unsupported)),
+                    };
+                    let port = client.read_u16().await?;
+                    targets
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(format!("{host}:{port}"));
+
+                    let mut server = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await?;
+                    client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+                    tokio::io::copy_bidirectional(&mut client, &mut server).await?;
+                    Ok(())
+                });
+            }
+        });
+        Ok((address, targets))
+    }
+
+    #[cfg(feature = "socks")]
+    #[tokio::test]
+    async fn hyper_socks5_proxy() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users", |_| respond(200, "ok"));
+        let port = server.address().port();
+        let (proxy, targets) = socks5_proxy().await?;
+        let url = format!("http://api.example.invalid:{port}/users");
+
+        // socks5 resolves locally, socks5h leaves it to the proxy
+        for scheme in ["socks5", "socks5h"] {
+            let body = Hyper::new()
+                .set_proxy(Proxy::all(&format!("{scheme}://{proxy}"))?)
+                .set_dns(Dns::new().add_override("api.example.invalid", [server.address().ip()]))
+                .request_raw(Method::GET, url.as_str(), None)
+                .await?;
+            assert_eq!(body, b"ok");
+        }
+
+        assert_eq!(
+            *targets
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+            [
+                format!("127.0.0.1:{port}"),
+                format!("api.example.invalid:{port}")
+            ]
+        );
+        assert_eq!(server.received().len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hyper_dns_override() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users", |_| respond(200, "ok"));
+        let address = server.address();
+
+        let body = Hyper::new()
+            .set_dns(Dns::new().add_override("api.example.invalid", [address.ip()]))
+            .request_raw(
+                Method::GET,
+                format!("http://api.example.invalid:{}/users", address.port()),
+                None,
+            )
+            .await?;
+
+        assert_eq!(body, b"ok");
+        assert_eq!(
+            server.received()[0].headers["host"],
+            format!("api.example.invalid:{}", address.port()).as_str()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "hyper-rustls")]
+    #[tokio::test]
+    async fn hyper_tls_pinning() -> crate::engines::hyper::Result<()> {
+        use crate::{
+            test_util::{CERTIFICATE, CERTIFICATE_PIN},
+            tls::Tls,
+        };
+
+        let server = Server::start_tls().await?;
+        server.route(Method::GET, "/", |_| respond(200, "pinned"));
+        let tls = Tls::new()
+            .set_system_roots(false)
+            .add_root_certificate(CERTIFICATE);
+
+        let body = Hyper::new()
+            .set_url(server.url())
+            .set_tls(tls.clone().add_pin(CERTIFICATE_PIN))
+            .request_raw(Method::GET, "/", None)
+            .await?;
+        assert_eq!(body, b"pinned");
+
+        // a trusted certificate with another key is still rejected
         assert!(Hyper::new()
-            .request::<Value>(Method::GET, "https://repo.toastxc.xyz/empty.json", None)
+            .set_url(server.url())
+            .set_tls(tls.clone().add_pin([0; 32]))
+            .request_raw(Method::GET, "/", None)
             .await
-            .is_ok());
+            .is_err());
+        assert_eq!(server.received().len(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "hyper-rustls")]
+    #[tokio::test]
+    async fn hyper_http2() -> crate::engines::hyper::Result<()> {
+        use crate::{http2::Http2, test_util::CERTIFICATE, tls::Tls};
+        use hyper::Version;
+
+        // negotiated through ALPN
+        let server = Server::start_tls().await?;
+        server.route(Method::GET, "/", |_| respond(200, "ok"));
+        let response = Hyper::new()
+            .set_url(server.url())
+            .set_tls(
+                Tls::new()
+                    .set_system_roots(false)
+                    .add_root_certificate(CERTIFICATE),
+            )
+            .common(Method::GET, String::from("/"), None)
+            .await?;
+        assert_eq!(response.version, Version::HTTP_2);
+
+        // with prior knowledge over plain TCP
+        let server = Server::start().await?;
+        server.route(Method::GET, "/", |_| respond(200, "ok"));
+        let response = Hyper::new()
+            .set_url(server.url())
+            .set_http2(Http2::only())
+            .common(Method::GET, String::from("/"), None)
+            .await?;
+        assert_eq!(response.version, Version::HTTP_2);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hyper_unix_socket() -> crate::engines::hyper::Result<()> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = std::env::temp_dir().join(format!("reywen-http-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        let handle = tokio::spawn(async move {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return String::new();
+            };
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                match stream.read_u8().await {
+                    Ok(byte) => head.push(byte),
+                    Err(_) => break,
+                }
+            }
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n[]")
+                .await;
+            String::from_utf8_lossy(&head).into_owned()
+        });
+
+        let body = Hyper::new()
+            .set_unix_socket(&path)
+            .request_raw(Method::GET, "/containers/json", None)
+            .await;
+        let head = handle.await.unwrap_or_default();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(body?, b"[]");
+        assert!(head.starts_with("GET /containers/json HTTP/1.1\r\n"));
+        assert!(head.contains("host: localhost\r\n"));
+        Ok(())
+    }
+
+    #[cfg(feature = "cookies")]
+    #[tokio::test]
+    async fn hyper_cookies() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::POST, "/auth/login", |_| {
+            let mut response = respond(303, Vec::new());
+            let headers = response.headers_mut();
+            headers.insert(
+                "set-cookie",
+                hyper::header::HeaderValue::from_static("session=abc; Path=/"),
+            );
+            headers.insert(
+                "location",
+                hyper::header::HeaderValue::from_static("/users/@me"),
+            );
+            response
+        });
+        server.route(Method::GET, "/users/@me", |_| respond(200, "{}"));
+        let jar = crate::cookies::Jar::new();
+        let client = Hyper::new()
+            .set_url(server.url())
+            .set_cookie_jar(jar.clone());
+
+        // the cookie set by the redirect is sent to its target
+        client
+            .request_raw(Method::POST, "/auth/login", None)
+            .await?;
+        client.request_raw(Method::GET, "/users/@me", None).await?;
+        let received = server.received();
+        assert!(!received[0].headers.contains_key("cookie"));
+        assert_eq!(received[1].headers["cookie"], "session=abc");
+        assert_eq!(received[2].headers["cookie"], "session=abc");
+        assert!(jar.cookie_header(&server.url().parse()?).is_some());
+        Ok(())
+    }
+
+    #[cfg(feature = "vcr")]
+    #[tokio::test]
+    async fn hyper_vcr() -> crate::engines::hyper::Result<()> {
+        use crate::vcr::Vcr;
+
+        let path = std::env::temp_dir().join(format!("reywen-http-{}.json", std::process::id()));
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users/@me", |_| {
+            respond(200, r#"{"_id":"01"}"#)
+        });
+        let client = Hyper::new()
+            .set_url(server.url())
+            .add_header("x-session-token", "secret")?;
+
+        client
+            .clone()
+            .set_vcr(Vcr::record(&path).redact_header("x-session-token"))
+            .request_raw(Method::GET, "/users/@me", None)
+            .await?;
+        drop(server);
+
+        let replay = client.clone().set_vcr(Vcr::replay(&path)?);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            replay.request_raw(Method::GET, "/users/@me", None).await?,
+            br#"{"_id":"01"}"#
+        );
+        assert!(matches!(
+            replay.request_raw(Method::GET, "/users/@me", None).await,
+            Err(Error::Vcr(_))
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "cache")]
+    #[tokio::test]
+    async fn hyper_cache() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/bazaar", |request| {
+            let mut response = if request.headers.contains_key("if-none-match") {
+                respond(304, Vec::new())
+            } else {
+                respond(200, "{}")
+            };
+            let headers = response.headers_mut();
+            headers.insert(
+                "cache-control",
+                hyper::header::HeaderValue::from_static("no-cache"),
+            );
+            headers.insert("etag", hyper::header::HeaderValue::from_static("\"v1\""));
+            response
+        });
+        let client = Hyper::new()
+            .set_url(server.url())
+            .set_cache(crate::cache::Cache::memory());
+
+        for _ in 0..2 {
+            assert_eq!(
+                client.request_raw(Method::GET, "/bazaar", None).await?,
+                b"{}"
+            );
+        }
+        let received = server.received();
+        assert!(!received[0].headers.contains_key("if-none-match"));
+        assert_eq!(received[1].headers["if-none-match"], "\"v1\"");
+        Ok(())
+    }
+
+    #[cfg(feature = "single-flight")]
+    #[tokio::test]
+    async fn hyper_single_flight() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users/01", |_| respond(200, "{}"));
+        let client = Hyper::new()
+            .set_url(server.url())
+            .set_single_flight(crate::single_flight::SingleFlight::new());
+
+        let (first, second, third) = tokio::join!(
+            client.request_raw(Method::GET, "/users/01", None),
+            client.request_raw(Method::GET, "/users/01", None),
+            client.request_raw(Method::GET, "/users/01", None),
+        );
+        assert_eq!(
+            (first?, second?, third?),
+            (b"{}".to_vec(), b"{}".to_vec(), b"{}".to_vec())
+        );
+        assert_eq!(server.received().len(), 1);
+
+        // finished requests are not shared
+        client.request_raw(Method::GET, "/users/01", None).await?;
+        assert_eq!(server.received().len(), 2);
+        Ok(())
+    }
+
+    #[cfg(feature = "limit")]
+    #[tokio::test]
+    async fn hyper_limit() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users/01", |_| respond(200, "{}"));
+        let client = Hyper::new().set_url(server.url()).set_limit(
+            crate::limit::Limit::new()
+                .set_max_in_flight(1)
+                .set_max_queued(0),
+        );
+
+        let (first, second) = tokio::join!(
+            client.request_raw(Method::GET, "/users/01", None),
+            client.request_raw(Method::GET, "/users/01", None),
+        );
+        assert_eq!(first?, b"{}".to_vec());
+        assert!(matches!(second, Err(Error::QueueFull(_))));
+
+        // the slot is released with the response
+        client.request_raw(Method::GET, "/users/01", None).await?;
+        assert_eq!(server.received().len(), 2);
+        Ok(())
+    }
+
+    #[cfg(feature = "circuit-breaker")]
+    #[tokio::test]
+    async fn hyper_circuit_breaker() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users/01", |_| respond(503, ""));
+        let client = Hyper::new().set_url(server.url()).set_circuit_breaker(
+            crate::breaker::Breaker::new()
+                .set_minimum_requests(1)
+                .set_cool_down(std::time::Duration::MAX),
+        );
+
+        let response = client
+            .common(Method::GET, String::from("/users/01"), None)
+            .await?;
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(matches!(
+            client.request_raw(Method::GET, "/users/01", None).await,
+            Err(Error::CircuitOpen(_))
+        ));
+        assert_eq!(server.received().len(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "sse")]
+    #[tokio::test]
+    async fn hyper_event_source() -> crate::engines::hyper::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/events", |request| {
+            if request.headers.contains_key("last-event-id") {
+                respond(204, "")
+            } else {
+                respond(200, "retry: 0\nid: 1\nevent: join\ndata: {\"n\":1}\n\n")
+            }
+        });
+        let mut events = Hyper::new().set_url(server.url()).event_source("/events");
+
+        let event = events.next().await.transpose()?;
+        assert_eq!(
+            event.map(|event| (event.event, event.data)),
+            Some((String::from("join"), String::from("{\"n\":1}")))
+        );
+        // the stream ended, the reconnection is answered with 204 No Content
+        assert!(events.next().await.is_none());
+        drop(events);
+
+        let received = server.received();
+        assert_eq!(received[0].headers["accept"], "text/event-stream");
+        assert_eq!(received[1].headers["last-event-id"], "1");
+        Ok(())
+    }
+
+    #[cfg(feature = "download")]
+    #[tokio::test]
+    async fn hyper_download() -> crate::engines::hyper::Result<()> {
+        use crate::download::{Download, Mismatch};
+
+        // serves `hello world`, from the sixth byte to requests resuming the same version
+        let server = Server::start().await?;
+        server.route(Method::GET, "/backup", |request| {
+            let resumed = request
+                .headers
+                .get("range")
+                .is_some_and(|range| range == "bytes=6-")
+                && request
+                    .headers
+                    .get("if-range")
+                    .is_some_and(|tag| tag == "\"v1\"");
+            let mut response = if resumed {
+                let mut response = respond(206, "world");
+                response.headers_mut().insert(
+                    "content-range",
+                    hyper::header::HeaderValue::from_static("bytes 6-10/11"),
+                );
+                response
+            } else {
+                respond(200, "hello world")
+            };
+            response
+                .headers_mut()
+                .insert("etag", hyper::header::HeaderValue::from_static("\"v1\""));
+            response
+        });
+        let client = Hyper::new().set_url(server.url());
+
+        let file = std::env::temp_dir().join(format!("reywen-http-{}.bak", std::process::id()));
+        let part = file.with_extension("bak.part");
+        let validator = file.with_extension("bak.part.validator");
+        std::fs::write(&part, "hello ")?;
+        std::fs::write(&validator, "\"v1\"")?;
+
+        let download = Download::new()
+            .set_sha256("B94D27B9934D3E08A52E52D7DA7DABFAC484EFE37A5380EE9088F7ACE2EFCDE9");
+        let progress = download.subscribe();
+        let size = client.download_to("/backup", &file, &download).await;
+        let contents = std::fs::read_to_string(&file);
+        let _ = std::fs::remove_file(&file);
+        assert_eq!(size?, 11);
+        assert_eq!(contents?, "hello world");
+        assert!(!part.exists() && !validator.exists());
+        assert_eq!(
+            *progress.borrow(),
+            crate::download::Progress {
+                downloaded: 11,
+                total: Some(11),
+                resumed_from: 6,
+            }
+        );
+
+        // without a partial file the whole body is downloaded, and removed when it does not match
+        let download = Download::new().set_sha256("00");
+        assert!(matches!(
+            client.download_to("/backup", &file, &download).await,
+            Err(Error::Download(Mismatch::Checksum { .. }))
+        ));
+        assert!(!part.exists() && !file.exists());
+        assert!(!server.received()[1].headers.contains_key("range"));
+        Ok(())
+    }
+
+    #[cfg(feature = "paginate")]
+    #[tokio::test]
+    async fn hyper_paginate() -> crate::engines::hyper::Result<()> {
+        use futures_util::StreamExt;
+
+        let server = Server::start().await?;
+        server.route(Method::GET, "/members", |request| {
+            if request.uri.ends_with("page=2") {
+                return respond(200, r#"{"members":[3]}"#);
+            }
+            let mut response = respond(200, r#"{"members":[1,2]}"#);
+            response.headers_mut().insert(
+                "link",
+                hyper::header::HeaderValue::from_static(r#"</members?page=2>; rel="next""#),
+            );
+            response
+        });
+        let mut paginator = Hyper::new()
+            .set_url(server.url())
+            .paginate::<u32>("/members", crate::paginate::Next::Link);
+        paginator.items = String::from("/members");
+
+        let members = paginator.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(
+            members.into_iter().collect::<Result<Vec<_>, _>>()?,
+            [1, 2, 3]
+        );
+        assert_eq!(server.received()[1].uri, "/members?page=2");
+        Ok(())
+    }
+
+    #[cfg(feature = "poll")]
+    #[tokio::test]
+    async fn hyper_poll() -> crate::engines::hyper::Result<()> {
+        use futures_util::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // not modified on the second poll, changed on the third
+        let server = Server::start().await?;
+        let polls = AtomicUsize::new(0);
+        server.route(Method::GET, "/status", move |request| {
+            let (body, etag) = match polls.fetch_add(1, Ordering::Relaxed) {
+                0 | 1 => ("1", "\"v1\""),
+                _ => ("2", "\"v2\""),
+            };
+            let mut response = if request
+                .headers
+                .get("if-none-match")
+                .is_some_and(|sent| sent == etag)
+            {
+                respond(304, Vec::new())
+            } else {
+                respond(200, body)
+            };
+            response
+                .headers_mut()
+                .insert("etag", hyper::header::HeaderValue::from_static(etag));
+            response
+        });
+        let mut poller = Hyper::new().set_url(server.url()).poll("/status");
+        poller.interval = std::time::Duration::ZERO;
+        let mut changes = std::pin::pin!(poller.into_stream());
+
+        assert_eq!(changes.next().await.transpose()?, Some(b"1".to_vec()));
+        assert_eq!(changes.next().await.transpose()?, Some(b"2".to_vec()));
+        let received = server.received();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[1].headers["if-none-match"], "\"v1\"");
+        Ok(())
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    // the handshake callback of tungstenite returns a whole response as its error
+    #[allow(clippy::result_large_err)]
+    async fn hyper_websocket() -> crate::engines::hyper::Result<()> {
+        use crate::websocket::Message;
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite;
+
+        // greets with the user agent and echoes messages, `bye` closes the connection
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut user_agent = String::new();
+                let Ok(mut socket) =
+                    tokio_tungstenite::accept_hdr_async(stream, |request: &_, response| {
+                        let request: &tungstenite::handshake::server::Request = request;
+                        user_agent = request.headers()["user-agent"]
+                            .to_str()
+                            .unwrap_or_default()
+                            .to_owned();
+                        Ok(response)
+                    })
+                    .await
+                else {
+                    continue;
+                };
+                let _ = socket.send(tungstenite::Message::Text(user_agent)).await;
+                while let Some(Ok(message)) = socket.next().await {
+                    if message == tungstenite::Message::Text(String::from("bye")) {
+                        let _ = socket.close(None).await;
+                        break;
+                    }
+                    let _ = socket.send(message).await;
+                }
+            }
+        });
+
+        let mut socket = Hyper::new()
+            .set_url(format!("ws://{address}"))
+            .set_user_agent("bonfire")
+            .websocket("/");
+        socket.greeting = vec![Message::Text(String::from("hello"))];
+        socket.reconnect = Some(std::time::Duration::ZERO);
+
+        let text = |text: &str| Some(Message::Text(String::from(text)));
+        assert_eq!(socket.next().await.transpose()?, text("bonfire"));
+        assert_eq!(socket.next().await.transpose()?, text("hello"));
+        socket.send(Message::Binary(vec![1, 2])).await?;
+        assert_eq!(
+            socket.next().await.transpose()?,
+            Some(Message::Binary(vec![1, 2]))
+        );
+
+        // the greeting is sent again after reconnecting
+        socket.send(Message::Text(String::from("bye"))).await?;
+        assert_eq!(socket.next().await.transpose()?, text("bonfire"));
+        assert_eq!(socket.next().await.transpose()?, text("hello"));
+
+        socket.close().await;
+        assert!(socket.next().await.is_none());
+        drop(socket);
+        Ok(())
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn hyper_websocket_pong_timeout() -> crate::engines::hyper::Result<()> {
+        use crate::websocket::Message;
+        use futures_util::SinkExt;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite;
+
+        // sends the number of the connection and stops reading, so pings are never answered
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else {
+                    continue;
+                };
+                let number = (sockets.len() + 1).to_string();
+                let _ = socket.send(tungstenite::Message::Text(number)).await;
+                sockets.push(socket);
+            }
+        });
+
+        let mut socket = Hyper::new()
+            .set_url(format!("ws://{address}"))
+            .websocket("/");
+        socket.reconnect = Some(Duration::ZERO);
+        socket.ping_interval = Some(Duration::from_millis(50));
+        socket.pong_timeout = Some(Duration::from_millis(50));
+
+        let text = |text: &str| Some(Message::Text(String::from(text)));
+        assert_eq!(socket.next().await.transpose()?, text("1"));
+        // the silent connection is dropped after the ping and a new one is opened
+        assert_eq!(socket.next().await.transpose()?, text("2"));
+
+        socket.close().await;
+        drop(socket);
+        Ok(())
     }
 }
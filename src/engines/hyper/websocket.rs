@@ -0,0 +1,119 @@
+use crate::{
+    engines::{
+        hyper::{connector::Connector, Error, Hyper, Result},
+        Held,
+    },
+    websocket::{Connect, Incoming, Message, WebSocket},
+};
+use futures_util::{SinkExt, StreamExt};
+use hyper::{
+    header::{HeaderValue, USER_AGENT},
+    service::Service,
+    Uri,
+};
+use tokio_tungstenite::{
+    tungstenite::{self, client::IntoClientRequest},
+    WebSocketStream,
+};
+
+/// A WebSocket over a connection of the [`Connector`], with the proxy and TLS settings of the
+/// client.
+pub type Socket = Held<WebSocketStream<<Connector as Service<Uri>>::Response>>;
+
+impl Hyper {
+    /// `path` is appended to the URL, `ws://` and `wss://` as well as `http://` and `https://`
+    /// URLs are accepted.
+    #[must_use]
+    pub fn websocket(&self, path: impl Into<String>) -> WebSocket<Self> {
+        WebSocket::new(self.clone(), path.into())
+    }
+}
+
+impl Connect for Hyper {
+    type Socket = Socket;
+    type Error = Error;
+
+    async fn connect(&self, path: &str) -> Result<Socket> {
+        let mut url = self.url_for(path)?;
+        // held for as long as the socket is open
+        let slot = self.slot(url.as_str()).await?;
+        let (http, ws) = match url.scheme() {
+            "https" | "wss" => ("https", "wss"),
+            _ => ("http", "ws"),
+        };
+
+        // the connector picks TLS by the HTTP scheme
+        let _ = url.set_scheme(http);
+        let destination = url
+            .as_str()
+            .parse::<Uri>()
+            .map_err(hyper::http::Error::from)?;
+        let stream = self
+            .connector_with(true)?
+            .call(destination)
+            .await
+            .map_err(std::io::Error::other)?;
+
+        #[cfg(feature = "cookies")]
+        let cookie = self
+            .cookies
+            .as_ref()
+            .and_then(|jar| jar.cookie_header(&url));
+
+        let _ = url.set_scheme(ws);
+        let mut request = url.as_str().into_client_request()?;
+        let headers = request.headers_mut();
+        headers.extend(self.headers.clone());
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(crate::USER_AGENT))?,
+        );
+        #[cfg(feature = "cookies")]
+        if let Some(cookie) = cookie {
+            headers
+                .entry(hyper::header::COOKIE)
+                .or_insert(HeaderValue::from_str(&cookie)?);
+        }
+
+        let (socket, _) = tokio_tungstenite::client_async(request, stream).await?;
+        Ok(Held::new(socket, slot))
+    }
+
+    async fn send(socket: &mut Socket, message: Message) -> Result<()> {
+        Ok(socket
+            .send(match message {
+                Message::Text(text) => tungstenite::Message::Text(text),
+                Message::Binary(bytes) => tungstenite::Message::Binary(bytes),
+            })
+            .await?)
+    }
+
+    async fn receive(socket: &mut Socket) -> Option<Result<Incoming>> {
+        loop {
+            return match socket.next().await? {
+                Ok(tungstenite::Message::Text(text)) => {
+                    Some(Ok(Incoming::Message(Message::Text(text))))
+                }
+                Ok(tungstenite::Message::Binary(bytes)) => {
+                    Some(Ok(Incoming::Message(Message::Binary(bytes))))
+                }
+                Ok(tungstenite::Message::Pong(_)) => Some(Ok(Incoming::Pong)),
+                Ok(tungstenite::Message::Close(_))
+                | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    None
+                }
+                // pings are answered while reading
+                Ok(_) => continue,
+                Err(error) => Some(Err(error.into())),
+            };
+        }
+    }
+
+    async fn ping(socket: &mut Socket) -> Result<()> {
+        Ok(socket.send(tungstenite::Message::Ping(Vec::new())).await?)
+    }
+
+    async fn close(socket: &mut Socket) {
+        let _ = socket.close(None).await;
+    }
+}
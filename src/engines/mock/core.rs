@@ -0,0 +1,232 @@
+use crate::engines::mock::{Body, Call, Error, Mock, Reply, Result, Route, State};
+use http::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE, USER_AGENT},
+    HeaderMap, Method, StatusCode,
+};
+use std::{
+    str::FromStr,
+    sync::{MutexGuard, PoisonError},
+};
+
+impl Mock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Mock {
+    pub fn set_url(&mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self.to_owned()
+    }
+    pub fn set_user_agent(&mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self.to_owned()
+    }
+    pub fn set_content_type(&mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self.to_owned()
+    }
+    pub fn add_header(&mut self, key: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        self.headers.append(
+            HeaderName::from_str(key.into().as_str())?,
+            HeaderValue::from_str(value.into().as_str())?,
+        );
+        Ok(self.to_owned())
+    }
+    pub fn set_headers(&mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self.to_owned()
+    }
+    pub fn add_route(&mut self, route: Route) -> Self {
+        self.state().routes.push(route);
+        self.to_owned()
+    }
+
+    /// Every request received so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    // a panicking test must not hide the calls made before it from other clones
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Route {
+    /// Matches `method` requests to `path`, answering `200 OK` without a body.
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            headers: HeaderMap::new(),
+            body: None,
+            reply: Reply::status(StatusCode::OK),
+            times: None,
+        }
+    }
+    pub fn add_header(&mut self, key: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        self.headers.append(
+            HeaderName::from_str(key.into().as_str())?,
+            HeaderValue::from_str(value.into().as_str())?,
+        );
+        Ok(self.to_owned())
+    }
+    pub fn set_body(&mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(body.into());
+        self.to_owned()
+    }
+    pub fn set_reply(&mut self, reply: Reply) -> Self {
+        self.reply = reply;
+        self.to_owned()
+    }
+    pub fn set_times(&mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self.to_owned()
+    }
+
+    fn matches(&self, call: &Call) -> bool {
+        let path = call
+            .url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|start| &rest[start..]))
+            .unwrap_or("/");
+
+        self.times != Some(0)
+            && self.method == call.method
+            && (self.path == call.url || self.path == path)
+            && self.headers.iter().all(|(key, value)| {
+                call.headers
+                    .get_all(key)
+                    .iter()
+                    .any(|received| received == value)
+            })
+            && (self.body.is_none() || self.body == call.body)
+    }
+}
+
+impl Reply {
+    #[must_use]
+    pub fn status(status: StatusCode) -> Self {
+        Self::Response {
+            status,
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+    pub fn body(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self::Response {
+            status,
+            headers: HeaderMap::new(),
+            body: Some(body.into()),
+        }
+    }
+    #[cfg(feature = "serde")]
+    pub fn json(status: StatusCode, body: &impl serde::Serialize) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        Ok(Self::Response {
+            status,
+            headers,
+            body: Some(serde_json::to_vec(body)?),
+        })
+    }
+}
+
+// methods
+impl Body {
+    #[cfg(feature = "serde")]
+    pub fn serde_switch<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        match (self.body, self.status.as_u16()) {
+            (Some(data), 200) => Ok(serde_json::from_slice(&data)?),
+            (None, 200) | (_, 204) => Ok(serde_json::from_value(serde_json::Value::Null)?),
+            _ => Err(Error::StatusCode(self.status)),
+        }
+    }
+    pub fn bytes(self) -> Result<Vec<u8>> {
+        match (self.body, self.status.as_u16()) {
+            (Some(data), 200) => Ok(data),
+            (None, 204) | (None, 200) => Ok(Vec::new()),
+            _ => Err(Error::StatusCode(self.status)),
+        }
+    }
+}
+
+impl Mock {
+    /// Records the request and answers it from the first matching route.
+    // async like the network engines, so code under test calls it the same way
+    #[allow(clippy::unused_async)]
+    pub async fn common(
+        &self,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<Body> {
+        let mut headers = self.headers.clone();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(crate::USER_AGENT))?,
+        );
+        if let Some(content_type) = self.content_type.as_deref() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
+        }
+
+        let call = Call {
+            method,
+            url: format!("{}{path}", self.url),
+            headers,
+            body: data,
+        };
+
+        let mut state = self.state();
+        state.calls.push(call.clone());
+        let Some(route) = state.routes.iter_mut().find(|route| route.matches(&call)) else {
+            return Err(Error::Unmatched(format!("{} {}", call.method, call.url)));
+        };
+        if let Some(times) = &mut route.times {
+            *times -= 1;
+        }
+        let reply = route.reply.clone();
+        drop(state);
+
+        match reply {
+            Reply::Response {
+                status,
+                headers,
+                body,
+            } => Ok(Body {
+                body,
+                status,
+                headers,
+            }),
+            Reply::Timeout => Err(Error::Timeout),
+            Reply::ConnectionError => Err(Error::Connection),
+        }
+    }
+
+    pub async fn request_raw(
+        &self,
+        method: impl Into<Method>,
+        path: impl Into<String>,
+        data: impl Into<Option<Vec<u8>>>,
+    ) -> Result<Vec<u8>> {
+        self.common(method.into(), path.into(), data.into())
+            .await?
+            .bytes()
+    }
+
+    #[cfg(feature = "serde")]
+    pub async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        method: impl Into<Method>,
+        path: impl Into<String>,
+        data: impl Into<Option<Vec<u8>>>,
+    ) -> Result<T> {
+        self.common(method.into(), path.into(), data.into())
+            .await?
+            .serde_switch()
+    }
+}
@@ -0,0 +1,76 @@
+pub mod core;
+pub mod results;
+pub mod tests;
+
+// re-exports
+pub use crate::engines::mock::results::Error;
+pub use http::HeaderMap;
+pub use http::Method;
+pub use http::StatusCode;
+
+use std::sync::{Arc, Mutex};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Engine answering requests from scripted [`Route`]s, for tests without a network.
+///
+/// Clones share their routes and recorded calls, so a clone can be handed to the code under
+/// test while the original is used for assertions.
+#[derive(Debug, Clone, Default)]
+pub struct Mock {
+    pub url: String,
+    pub user_agent: Option<String>,
+    pub content_type: Option<String>,
+    pub headers: HeaderMap,
+    pub(crate) state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    pub(crate) routes: Vec<Route>,
+    pub(crate) calls: Vec<Call>,
+}
+
+/// Matches requests by method, path and optionally headers and body.
+///
+/// Routes are tried in the order they were added, the first match answers.
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub method: Method,
+    /// A full URL, or a path that is compared to the path and query of the request.
+    pub path: String,
+    /// Headers the request must contain, others are ignored.
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+    pub reply: Reply,
+    /// How many requests the route answers before it stops matching, `None` is unlimited.
+    pub times: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Response {
+        status: StatusCode,
+        headers: HeaderMap,
+        body: Option<Vec<u8>>,
+    },
+    /// Fails the request with [`Error::Timeout`].
+    Timeout,
+    /// Fails the request with [`Error::Connection`].
+    ConnectionError,
+}
+
+/// A request received by the mock, as the engine would have sent it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+pub struct Body {
+    pub body: Option<Vec<u8>>,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+}
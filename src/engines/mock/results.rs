@@ -0,0 +1,36 @@
+use http::header::{InvalidHeaderName, InvalidHeaderValue};
+use http::StatusCode;
+
+#[derive(Debug)]
+pub enum Error {
+    #[cfg(feature = "serde")]
+    Serde(serde_json::Error),
+    StatusCode(StatusCode),
+    HeaderName(InvalidHeaderName),
+    HeaderValue(InvalidHeaderValue),
+    /// A route replied with [`crate::engines::mock::Reply::Timeout`].
+    Timeout,
+    /// A route replied with [`crate::engines::mock::Reply::ConnectionError`].
+    Connection,
+    /// No route matched the request, holds its method and URL.
+    Unmatched(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serde(value)
+    }
+}
+
+impl From<InvalidHeaderName> for Error {
+    fn from(value: InvalidHeaderName) -> Self {
+        Self::HeaderName(value)
+    }
+}
+
+impl From<InvalidHeaderValue> for Error {
+    fn from(value: InvalidHeaderValue) -> Self {
+        Self::HeaderValue(value)
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use crate::engines::mock::{Error, Method, Mock, Reply, Route, StatusCode};
+
+    #[tokio::test]
+    async fn mock_routes() -> crate::engines::mock::Result<()> {
+        let mock = Mock::new()
+            .set_url("https://api.revolt.chat")
+            .add_header("x-session-token", "token")?
+            .add_route(
+                Route::new(Method::GET, "/users/@me")
+                    .add_header("x-session-token", "token")?
+                    .set_reply(Reply::body(StatusCode::OK, r#"{"_id":"01"}"#)),
+            )
+            .add_route(
+                Route::new(Method::POST, "/channels/01/messages")
+                    .set_body("hello")
+                    .set_times(1),
+            );
+
+        assert_eq!(
+            mock.request_raw(Method::GET, "/users/@me", None).await?,
+            br#"{"_id":"01"}"#
+        );
+        assert!(mock
+            .request_raw(
+                Method::POST,
+                "/channels/01/messages",
+                Some(b"hello".to_vec())
+            )
+            .await
+            .is_ok());
+        // used up
+        assert!(matches!(
+            mock.request_raw(
+                Method::POST,
+                "/channels/01/messages",
+                Some(b"hello".to_vec())
+            )
+            .await,
+            Err(Error::Unmatched(_))
+        ));
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].url, "https://api.revolt.chat/users/@me");
+        assert_eq!(calls[1].body.as_deref(), Some(b"hello".as_slice()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_failures() {
+        let mock = Mock::new()
+            .add_route(Route::new(Method::GET, "/slow").set_reply(Reply::Timeout))
+            .add_route(Route::new(Method::GET, "/down").set_reply(Reply::ConnectionError))
+            .add_route(
+                Route::new(Method::GET, "/missing").set_reply(Reply::status(StatusCode::NOT_FOUND)),
+            );
+
+        let shared = mock.clone();
+        assert!(matches!(
+            shared.request_raw(Method::GET, "/slow", None).await,
+            Err(Error::Timeout)
+        ));
+        assert!(matches!(
+            shared.request_raw(Method::GET, "/down", None).await,
+            Err(Error::Connection)
+        ));
+        assert!(matches!(
+            shared.request_raw(Method::GET, "/missing", None).await,
+            Err(Error::StatusCode(StatusCode::NOT_FOUND))
+        ));
+        assert_eq!(mock.calls().len(), 3);
+    }
+}
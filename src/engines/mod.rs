@@ -1,8 +1,63 @@
-#[cfg(feature = "hyper_engine")]
+#[cfg(feature = "__hyper")]
 pub mod hyper;
 
+#[cfg(feature = "mock_engine")]
+pub mod mock;
+
 #[cfg(feature = "reqwasm_engine")]
 pub mod reqwasm;
 
-#[cfg(feature = "reqwest_engine")]
+#[cfg(feature = "__reqwest")]
 pub mod reqwest;
+
+/// An open event stream, download or WebSocket of a native engine, holding the `limit` slots of
+/// the request that opened it until dropped.
+#[cfg(all(
+    any(feature = "__hyper", feature = "__reqwest"),
+    any(feature = "sse", feature = "download", feature = "websocket")
+))]
+pub struct Held<T> {
+    inner: T,
+    _slot: Slot,
+}
+
+#[cfg(all(
+    any(feature = "__hyper", feature = "__reqwest"),
+    any(feature = "sse", feature = "download", feature = "websocket")
+))]
+impl<T> Held<T> {
+    pub(crate) const fn new(inner: T, slot: Slot) -> Self {
+        Self { inner, _slot: slot }
+    }
+}
+
+#[cfg(all(
+    any(feature = "__hyper", feature = "__reqwest"),
+    any(feature = "sse", feature = "download", feature = "websocket")
+))]
+impl<T> std::ops::Deref for Held<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[cfg(all(
+    any(feature = "__hyper", feature = "__reqwest"),
+    any(feature = "sse", feature = "download", feature = "websocket")
+))]
+impl<T> std::ops::DerefMut for Held<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+/// The `limit` slots of a request, always `None` without the feature.
+#[cfg(all(any(feature = "__hyper", feature = "__reqwest"), feature = "limit"))]
+pub(crate) type Slot = Option<crate::limit::Permit>;
+#[cfg(all(
+    any(feature = "__hyper", feature = "__reqwest"),
+    not(feature = "limit")
+))]
+pub(crate) type Slot = Option<std::convert::Infallible>;
@@ -0,0 +1,45 @@
+use crate::{compression::Encoding, engines::reqwasm::results::Result};
+use js_sys::{Array, Reflect, Uint8Array};
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, ReadableWritablePair, Response};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type CompressionStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(format: &str) -> std::result::Result<CompressionStream, JsValue>;
+}
+
+/// Compresses through the browser's `CompressionStream` where it supports the encoding,
+/// otherwise in Rust.
+pub async fn compress(encoding: Encoding, data: &[u8]) -> Result<Uint8Array> {
+    let format = match encoding {
+        #[cfg(feature = "gzip")]
+        Encoding::Gzip => Some("gzip"),
+        #[cfg(feature = "deflate")]
+        Encoding::Deflate => Some("deflate"),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    };
+    let supported =
+        Reflect::has(&js_sys::global(), &JsValue::from_str("CompressionStream")).unwrap_or(false);
+
+    match format.filter(|_| supported) {
+        Some(format) => {
+            let stream = Blob::new_with_u8_array_sequence(&Array::of1(&Uint8Array::from(data)))?
+                .stream()
+                .pipe_through(
+                    CompressionStream::new(format)?.unchecked_ref::<ReadableWritablePair>(),
+                );
+            let buffer = JsFuture::from(
+                Response::new_with_opt_readable_stream(Some(&stream))?.array_buffer()?,
+            )
+            .await?;
+            Ok(Uint8Array::new(&buffer))
+        }
+        None => Ok(Uint8Array::from(encoding.encode(data)?.as_slice())),
+    }
+}
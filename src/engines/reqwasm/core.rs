@@ -1,9 +1,12 @@
-use crate::engines::reqwasm::{
-    results::{Error, Result},
-    Body, Reqwasm,
+use crate::{
+    engines::reqwasm::{
+        results::{Error, Result},
+        Body, Reqwasm,
+    },
+    redirect::Policy,
 };
 use js_sys::{Object, Reflect, Uint8Array};
-use reqwasm::http::{Headers, Method, Request};
+use reqwasm::http::{Headers, Method, Request, RequestRedirect};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::ReadableStreamDefaultReader;
@@ -36,6 +39,32 @@ impl Reqwasm {
         self.headers = headers;
         self.to_owned()
     }
+    /// The browser follows redirects on its own, only [`Policy::None`] changes its behaviour:
+    /// a redirect then fails with `Error::OpaqueRedirect`, the browser does not expose the
+    /// `3xx` response. Limits and custom policies are ignored.
+    pub fn set_redirect_policy(&mut self, policy: Policy) -> Self {
+        self.redirect = policy;
+        self.to_owned()
+    }
+    /// The server must accept the encoding, for a single request set it on a clone.
+    #[cfg(feature = "__compression")]
+    pub fn set_body_encoding(
+        &mut self,
+        encoding: impl Into<Option<crate::compression::Encoding>>,
+    ) -> Self {
+        self.body_encoding = encoding.into();
+        self.to_owned()
+    }
+    /// Fails requests to hosts that keep failing with `CircuitOpen`, clones of the breaker
+    /// share their circuits.
+    #[cfg(feature = "circuit-breaker")]
+    pub fn set_circuit_breaker(
+        &mut self,
+        breaker: impl Into<Option<crate::breaker::Breaker>>,
+    ) -> Self {
+        self.breaker = breaker.into();
+        self.to_owned()
+    }
 }
 
 impl Reqwasm {
@@ -46,7 +75,9 @@ impl Reqwasm {
         path: impl Into<String>,
         data: impl Into<Option<Vec<u8>>>,
     ) -> Result<T> {
-        self.common(method.into(), path.into(), Reqwasm::from_bytes(data.into())).await?.serde_switch()
+        self.common(method.into(), path.into(), self.body(data.into()).await?)
+            .await?
+            .serde_switch()
     }
 
     pub fn to_bytes(input: Option<JsValue>) -> Option<Vec<u8>> {
@@ -67,21 +98,77 @@ impl Reqwasm {
         path: impl Into<String>,
         data: impl Into<Option<Vec<u8>>>,
     ) -> Result<Vec<u8>> {
-       let a =  self.common(
-            method.into(),
-            format!("{}{}", self.url, path.into()),
-            Reqwasm::from_bytes(data.into()),
-        )
-        .await?;
+        let a = self
+            .common(
+                method.into(),
+                format!("{}{}", self.url, path.into()),
+                self.body(data.into()).await?,
+            )
+            .await?;
         Ok(a.body.unwrap_or_default())
     }
 
+    /// Bodies are sent as strings, unless they are compressed.
+    async fn body(&self, data: Option<Vec<u8>>) -> Result<Option<JsValue>> {
+        #[cfg(feature = "__compression")]
+        if let (Some(encoding), Some(data)) = (self.body_encoding, &data) {
+            return Ok(Some(
+                crate::engines::reqwasm::compression::compress(encoding, data)
+                    .await?
+                    .into(),
+            ));
+        }
+        Ok(Reqwasm::from_bytes(data))
+    }
+
+    /// `data` is expected to be compressed already when a body encoding is set.
     pub async fn common(
         &self,
         method: Method,
         url: String,
         data: Option<wasm_bindgen::JsValue>,
     ) -> Result<Body> {
+        // text bodies are sent as strings, compressed ones as byte arrays
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let body_size = data.as_ref().map_or(0, |data| {
+            data.as_string().map_or_else(
+                || Uint8Array::new(data).length() as usize,
+                |text| text.len(),
+            )
+        });
+        #[cfg(feature = "tracing")]
+        let span = crate::trace::span("reqwasm", &method.to_string(), &url, body_size);
+        #[cfg(feature = "metrics")]
+        let meter = crate::meter::Meter::new("reqwasm", &method.to_string(), &url, body_size);
+
+        #[cfg(feature = "circuit-breaker")]
+        let breaker_url = url.clone();
+        let response = self.send(method, url, data);
+        #[cfg(feature = "circuit-breaker")]
+        let response = crate::breaker::Breaker::call(
+            self.breaker.as_ref(),
+            &breaker_url,
+            response,
+            |body| body.status.get(),
+            Error::is_failure,
+        );
+        #[cfg(feature = "metrics")]
+        let response = meter.measure(response);
+        #[cfg(feature = "tracing")]
+        return crate::trace::instrument(span, response).await;
+        #[cfg(not(feature = "tracing"))]
+        response.await
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: String,
+        data: Option<wasm_bindgen::JsValue>,
+    ) -> Result<Body> {
+        #[cfg(feature = "__compression")]
+        let content_encoding = self.body_encoding.filter(|_| data.is_some());
+
         let mut request = Request::new(&url).body(data).method(method).header(
             "User-agent",
             self.user_agent.as_deref().unwrap_or(crate::USER_AGENT),
@@ -91,13 +178,25 @@ impl Reqwasm {
             request = request.header("Content-Type", content_type);
         }
 
+        #[cfg(feature = "__compression")]
+        if let Some(encoding) = content_encoding {
+            request = request.header("Content-Encoding", encoding.as_str());
+        }
+
+        if let Policy::None = self.redirect {
+            request = request.redirect(RequestRedirect::Manual);
+        }
+
         for (key, value) in self.headers.entries() {
             request = request.header(&key, &value);
         }
 
         let response = request.send().await?;
 
-        let status = response.status();
+        // manual redirects produce an opaque response without a status
+        let status = std::num::NonZeroU16::new(response.status()).ok_or(Error::OpaqueRedirect)?;
+        let url = response.url();
+        let redirected = response.redirected();
 
         let body = match response.body() {
             Some(body) => {
@@ -116,11 +215,31 @@ impl Reqwasm {
 
         Ok(Body {
             body,
-            status: unsafe { std::num::NonZeroU16::new_unchecked(status) },
+            status,
+            url,
+            redirected,
         })
     }
 }
 
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+impl crate::observe::Observed for Body {
+    fn status(&self) -> u16 {
+        self.status.get()
+    }
+    #[cfg(feature = "tracing")]
+    fn protocol_version(&self) -> Option<&'static str> {
+        None
+    }
+    fn body_size(&self) -> usize {
+        self.body.as_ref().map_or(0, Vec::len)
+    }
+    /// Browsers do not expose the number of redirects, a followed chain counts as one.
+    fn redirect_count(&self) -> usize {
+        usize::from(self.redirected)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl Body {
     pub fn serde_switch<T: serde::de::DeserializeOwned>(self) -> Result<T> {
@@ -131,3 +250,123 @@ impl Body {
         }
     }
 }
+
+#[cfg(feature = "sse")]
+impl Reqwasm {
+    /// Opens `path` as a `text/event-stream` once the first event is awaited.
+    #[must_use]
+    pub fn event_source(&self, path: impl Into<String>) -> crate::sse::EventSource<Self> {
+        crate::sse::EventSource::new(self.clone(), path.into())
+    }
+}
+
+#[cfg(feature = "sse")]
+impl crate::sse::Connect for Reqwasm {
+    type Response = ReadableStreamDefaultReader;
+    type Error = Error;
+
+    async fn connect(
+        &self,
+        path: &str,
+        last_event_id: Option<&str>,
+    ) -> Result<Option<ReadableStreamDefaultReader>> {
+        let mut request = Request::new(&format!("{}{path}", self.url))
+            .method(Method::GET)
+            .header("Accept", "text/event-stream")
+            .header("Cache-Control", "no-cache");
+        for (key, value) in self.headers.entries() {
+            request = request.header(&key, &value);
+        }
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id);
+        }
+
+        let response = request.send().await?;
+        match (response.status(), response.body()) {
+            (200, Some(body)) => Ok(Some(body.get_reader().dyn_into().map_err(JsValue::from)?)),
+            (200 | 204, _) => Ok(None),
+            (status, _) => Err(Error::StatusCode(
+                std::num::NonZeroU16::new(status).ok_or(Error::OpaqueRedirect)?,
+            )),
+        }
+    }
+
+    async fn chunk(reader: &mut ReadableStreamDefaultReader) -> Option<Vec<u8>> {
+        let result: Object = JsFuture::from(reader.read()).await.ok()?.dyn_into().ok()?;
+        if Reflect::get(&result, &JsValue::from_str("done"))
+            .ok()?
+            .as_bool()
+            == Some(true)
+        {
+            return None;
+        }
+        let value: Uint8Array = Reflect::get(&result, &JsValue::from_str("value"))
+            .ok()?
+            .dyn_into()
+            .ok()?;
+        Some(value.to_vec())
+    }
+}
+
+#[cfg(feature = "poll")]
+impl Reqwasm {
+    /// Polls `path` with `GET` once the first body is awaited.
+    #[must_use]
+    pub fn poll(&self, path: impl Into<String>) -> crate::poll::Poller<Self> {
+        crate::poll::Poller::new(self.clone(), path.into())
+    }
+}
+
+/// Browsers revalidate with ETags in their own cache and hide `304 Not Modified`, changes are
+/// detected by the body alone.
+#[cfg(feature = "poll")]
+impl crate::poll::Fetch for Reqwasm {
+    type Error = Error;
+
+    async fn fetch(&self, path: &str, _: Option<&str>) -> Result<Option<crate::poll::Fetched>> {
+        let response = self
+            .common(Method::GET, format!("{}{path}", self.url), None)
+            .await?;
+        match response.status.get() {
+            200 => Ok(Some(crate::poll::Fetched {
+                body: response.body.unwrap_or_default(),
+                etag: None,
+            })),
+            _ => Err(Error::StatusCode(response.status)),
+        }
+    }
+}
+
+#[cfg(feature = "paginate")]
+impl Reqwasm {
+    /// Pages through `path` with `GET` once the first item is awaited.
+    #[must_use]
+    pub fn paginate<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        next: crate::paginate::Next,
+    ) -> crate::paginate::Paginator<Self, T> {
+        crate::paginate::Paginator::new(self.clone(), path, next)
+    }
+}
+
+/// The response headers are not kept, [`crate::paginate::Next::Link`] finds no next page.
+#[cfg(feature = "paginate")]
+impl crate::paginate::Get for Reqwasm {
+    type Error = Error;
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.url)
+    }
+
+    async fn get(&self, url: &str) -> Result<crate::paginate::Response> {
+        let response = self.common(Method::GET, url.to_owned(), None).await?;
+        match response.status.get() {
+            200 => Ok(crate::paginate::Response {
+                body: response.body.unwrap_or_default(),
+                link: None,
+            }),
+            _ => Err(Error::StatusCode(response.status)),
+        }
+    }
+}
@@ -1,6 +1,10 @@
+#[cfg(feature = "__compression")]
+pub mod compression;
 pub mod core;
 pub mod results;
 pub mod tests;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 // re-exports
 pub use reqwasm::http::{Headers, Method};
@@ -9,6 +13,10 @@ pub use reqwasm::http::{Headers, Method};
 pub struct Body {
     pub body: Option<Vec<u8>>,
     pub status: std::num::NonZeroU16,
+    /// The URL of the final response, after the browser followed redirects.
+    pub url: String,
+    /// Browsers do not expose the redirect chain, only whether one was followed.
+    pub redirected: bool,
 }
 
 #[derive(Debug, Default)]
@@ -17,6 +25,12 @@ pub struct Reqwasm {
     pub user_agent: Option<String>,
     pub content_type: Option<String>,
     pub headers: Headers,
+    pub redirect: crate::redirect::Policy,
+    /// Compresses request bodies and sets `Content-Encoding` accordingly.
+    #[cfg(feature = "__compression")]
+    pub body_encoding: Option<crate::compression::Encoding>,
+    #[cfg(feature = "circuit-breaker")]
+    pub breaker: Option<crate::breaker::Breaker>,
 }
 
 impl Reqwasm {
@@ -32,6 +46,11 @@ impl Clone for Reqwasm {
             user_agent: self.user_agent.clone(),
             content_type: self.content_type.clone(),
             headers: header_clone(self.headers.entries()),
+            redirect: self.redirect.clone(),
+            #[cfg(feature = "__compression")]
+            body_encoding: self.body_encoding,
+            #[cfg(feature = "circuit-breaker")]
+            breaker: self.breaker.clone(),
         }
     }
     fn clone_from(&mut self, source: &Self) {
@@ -39,6 +58,15 @@ impl Clone for Reqwasm {
         self.user_agent = source.user_agent.clone();
         self.content_type = source.content_type.clone();
         self.headers = header_clone(source.headers.entries());
+        self.redirect = source.redirect.clone();
+        #[cfg(feature = "__compression")]
+        {
+            self.body_encoding = source.body_encoding;
+        }
+        #[cfg(feature = "circuit-breaker")]
+        {
+            self.breaker = source.breaker.clone();
+        }
     }
 }
 
@@ -55,6 +83,8 @@ impl Default for Body {
         Self {
             body: None,
             status: unsafe { std::num::NonZeroU16::new_unchecked(200) },
+            url: String::new(),
+            redirected: false,
         }
     }
 }
@@ -11,6 +11,18 @@ pub enum Error {
     #[cfg(feature = "serde")]
     Serde(serde_json::Error),
     StatusCode(std::num::NonZeroU16),
+    Io(std::io::Error),
+
+    /// The browser does not expose redirect responses, returned when a redirect is
+    /// met under `Policy::None`.
+    OpaqueRedirect,
+    /// The circuit of the host is open after too many failed requests.
+    #[cfg(feature = "circuit-breaker")]
+    CircuitOpen(crate::breaker::CircuitOpen),
+    #[cfg(feature = "msgpack")]
+    MsgPackEncode(rmp_serde::encode::Error),
+    #[cfg(feature = "msgpack")]
+    MsgPackDecode(rmp_serde::decode::Error),
 }
 
 impl From<reqwasm::Error> for Error {
@@ -29,6 +41,14 @@ impl Debug for Error {
             Error::Serde(a) => format!("{:?}", a),
 
             Error::StatusCode(a) => format!("{:?}", a),
+            Error::Io(a) => format!("{:?}", a),
+            Error::OpaqueRedirect => String::from("OpaqueRedirect"),
+            #[cfg(feature = "circuit-breaker")]
+            Error::CircuitOpen(a) => format!("CircuitOpen({:?})", a),
+            #[cfg(feature = "msgpack")]
+            Error::MsgPackEncode(a) => format!("{:?}", a),
+            #[cfg(feature = "msgpack")]
+            Error::MsgPackDecode(a) => format!("{:?}", a),
         };
         write!(f, "{data}")
     }
@@ -41,6 +61,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
 impl From<wasm_bindgen::JsError> for Error {
     fn from(value: wasm_bindgen::JsError) -> Self {
         Self::Js(value)
@@ -53,4 +79,38 @@ impl From<wasm_bindgen::JsValue> for Error {
     }
 }
 
+#[cfg(feature = "circuit-breaker")]
+impl From<crate::breaker::CircuitOpen> for Error {
+    fn from(value: crate::breaker::CircuitOpen) -> Self {
+        Self::CircuitOpen(value)
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+impl Error {
+    /// The host could not be reached or answered with a server error, counted by the
+    /// circuit breaker. Other errors are left uncounted.
+    pub(crate) fn is_failure(&self) -> bool {
+        match self {
+            Self::Engine(_) => true,
+            Self::StatusCode(status) => status.get() >= 500,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(value: rmp_serde::encode::Error) -> Self {
+        Self::MsgPackEncode(value)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(value: rmp_serde::decode::Error) -> Self {
+        Self::MsgPackDecode(value)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
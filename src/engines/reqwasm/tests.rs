@@ -9,3 +9,18 @@ async fn reqwasm_engine() {
         .await
         .is_ok());
 }
+
+// compared against the Rust encoder, which the browser's `CompressionStream` replaces
+#[cfg(all(test, feature = "gzip"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn reqwasm_compression_stream() {
+    use crate::compression::Encoding;
+
+    let compressed = crate::engines::reqwasm::compression::compress(Encoding::Gzip, b"revolt")
+        .await
+        .map(|compressed| compressed.to_vec());
+    assert!(matches!(
+        compressed.map(|compressed| Encoding::Gzip.decode(&compressed)),
+        Ok(Ok(decoded)) if decoded == b"revolt"
+    ));
+}
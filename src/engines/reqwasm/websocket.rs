@@ -0,0 +1,134 @@
+use crate::{
+    engines::reqwasm::{
+        results::{Error, Result},
+        Reqwasm,
+    },
+    websocket::{Connect, Incoming, Message, WebSocket},
+};
+use js_sys::{ArrayBuffer, Uint8Array};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use wasm_bindgen::{closure::Closure, JsCast, JsError, JsValue};
+use web_sys::{BinaryType, MessageEvent};
+
+/// A browser WebSocket, closed when dropped.
+pub struct Socket {
+    socket: web_sys::WebSocket,
+    receiver: UnboundedReceiver<Frame>,
+    /// Kept alive for as long as the socket calls them.
+    _callbacks: [Closure<dyn FnMut(JsValue)>; 4],
+}
+
+enum Frame {
+    Open,
+    Message(Message),
+    /// Browsers give no reason for errors.
+    Error,
+    Close,
+}
+
+impl Reqwasm {
+    /// `path` is appended to the URL, `ws://` and `wss://` as well as `http://` and `https://`
+    /// URLs are accepted.
+    ///
+    /// Browsers do not let WebSockets set headers or a user agent, they are sent without the
+    /// headers of the client.
+    #[must_use]
+    pub fn websocket(&self, path: impl Into<String>) -> WebSocket<Self> {
+        WebSocket::new(self.clone(), path.into())
+    }
+}
+
+impl Connect for Reqwasm {
+    type Socket = Socket;
+    type Error = Error;
+    const PONGS: bool = false;
+
+    async fn connect(&self, path: &str) -> Result<Socket> {
+        let url = format!("{}{path}", self.url);
+        let url = match url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some(("http", rest)) => format!("ws://{rest}"),
+            _ => url,
+        };
+
+        let socket = web_sys::WebSocket::new(&url)?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        let (sender, mut receiver) = unbounded_channel();
+        let callback = |frame: fn(JsValue) -> Frame| {
+            let sender = sender.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+                let _ = sender.send(frame(event));
+            })
+        };
+        let callbacks = [
+            callback(|_| Frame::Open),
+            callback(|event| {
+                let data = event.unchecked_into::<MessageEvent>().data();
+                Frame::Message(match data.as_string() {
+                    Some(text) => Message::Text(text),
+                    None => Message::Binary(
+                        Uint8Array::new(&data.unchecked_into::<ArrayBuffer>()).to_vec(),
+                    ),
+                })
+            }),
+            callback(|_| Frame::Error),
+            callback(|_| Frame::Close),
+        ];
+        socket.set_onopen(Some(callbacks[0].as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(callbacks[1].as_ref().unchecked_ref()));
+        socket.set_onerror(Some(callbacks[2].as_ref().unchecked_ref()));
+        socket.set_onclose(Some(callbacks[3].as_ref().unchecked_ref()));
+
+        // the socket is closed when dropped, also while opening
+        let mut socket = Socket {
+            socket,
+            receiver: unbounded_channel().1,
+            _callbacks: callbacks,
+        };
+        match receiver.recv().await {
+            Some(Frame::Open) => {
+                socket.receiver = receiver;
+                Ok(socket)
+            }
+            _ => Err(Error::Js(JsError::new("websocket connection failed"))),
+        }
+    }
+
+    async fn send(socket: &mut Socket, message: Message) -> Result<()> {
+        match message {
+            Message::Text(text) => socket.socket.send_with_str(&text)?,
+            Message::Binary(bytes) => socket.socket.send_with_u8_array(&bytes)?,
+        }
+        Ok(())
+    }
+
+    async fn receive(socket: &mut Socket) -> Option<Result<Incoming>> {
+        loop {
+            return match socket.receiver.recv().await? {
+                Frame::Message(message) => Some(Ok(Incoming::Message(message))),
+                Frame::Error => Some(Err(Error::Js(JsError::new("websocket error")))),
+                Frame::Close => None,
+                Frame::Open => continue,
+            };
+        }
+    }
+
+    /// Browsers answer and send pings themselves.
+    async fn ping(_: &mut Socket) -> Result<()> {
+        Ok(())
+    }
+
+    async fn close(socket: &mut Socket) {
+        let _ = socket.socket.close();
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onerror(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
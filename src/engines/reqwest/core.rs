@@ -1,6 +1,15 @@
-use crate::engines::reqwest::{
-    results::{Error, Result},
-    Reqwest, ReqwestBody,
+#[cfg(any(feature = "sse", feature = "download"))]
+use crate::engines::Held;
+use crate::{
+    dns::Dns,
+    engines::reqwest::{
+        results::{Error, Result},
+        Reqwest, ReqwestBody,
+    },
+    http2::Http2,
+    proxy::Proxy,
+    redirect::{Action, Attempt, Policy, TooManyRedirects},
+    tcp::Tcp,
 };
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, USER_AGENT},
@@ -8,7 +17,11 @@ use reqwest::{
 };
 #[cfg(feature = "serde")]
 use serde::de::DeserializeOwned;
-use std::str::FromStr;
+use std::{
+    net::IpAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 #[cfg(feature = "serde")]
 // impl ReqwestBody {
 //     fn serde_switch<T: DeserializeOwned>(self) -> Result<T> {
@@ -37,6 +50,37 @@ impl ReqwestBody {
     }
 }
 
+#[cfg(feature = "cache")]
+impl From<crate::cache::Entry> for ReqwestBody {
+    fn from(entry: crate::cache::Entry) -> Self {
+        Self {
+            status: entry.status(),
+            headers: entry.headers(),
+            url: entry.url,
+            history: Vec::new(),
+            version: reqwest::Version::HTTP_11,
+            body: Some(entry.body),
+        }
+    }
+}
+
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+impl crate::observe::Observed for ReqwestBody {
+    fn status(&self) -> u16 {
+        self.status.as_u16()
+    }
+    #[cfg(feature = "tracing")]
+    fn protocol_version(&self) -> Option<&'static str> {
+        crate::observe::protocol_version(self.version)
+    }
+    fn body_size(&self) -> usize {
+        self.body.as_ref().map_or(0, Vec::len)
+    }
+    fn redirect_count(&self) -> usize {
+        self.history.len()
+    }
+}
+
 impl Reqwest {
     pub fn set_url(&mut self, url: impl Into<String>) -> Self {
         self.url = url.into();
@@ -61,6 +105,92 @@ impl Reqwest {
         self.headers = headers;
         self.to_owned()
     }
+    pub fn set_redirect_policy(&mut self, policy: Policy) -> Self {
+        self.redirect = policy;
+        self.to_owned()
+    }
+    pub fn set_http2(&mut self, http2: Http2) -> Self {
+        self.http2 = http2;
+        self.to_owned()
+    }
+    /// reqwest ignores `happy_eyeballs_timeout` and waits 300ms before racing the other family.
+    pub fn set_dns(&mut self, dns: Dns) -> Self {
+        self.dns = dns;
+        self.to_owned()
+    }
+    /// Socket buffer sizes are not supported by reqwest and fail the request.
+    pub fn set_tcp(&mut self, tcp: Tcp) -> Self {
+        self.tcp = tcp;
+        self.to_owned()
+    }
+    /// Without a proxy reqwest uses the one set in the environment, if any.
+    pub fn set_proxy(&mut self, proxy: impl Into<Option<Proxy>>) -> Self {
+        self.proxy = proxy.into();
+        self.to_owned()
+    }
+    #[cfg(feature = "__tls")]
+    pub fn set_tls(&mut self, tls: crate::tls::Tls) -> Self {
+        self.tls = tls;
+        self.to_owned()
+    }
+    /// Decompression is enabled by default, disable it to receive the raw compressed bytes.
+    #[cfg(feature = "__compression")]
+    pub fn set_decompress(&mut self, decompress: bool) -> Self {
+        self.no_decompress = !decompress;
+        self.to_owned()
+    }
+    /// The server must accept the encoding, for a single request set it on a clone.
+    #[cfg(feature = "__compression")]
+    pub fn set_body_encoding(
+        &mut self,
+        encoding: impl Into<Option<crate::compression::Encoding>>,
+    ) -> Self {
+        self.body_encoding = encoding.into();
+        self.to_owned()
+    }
+    /// Sends and stores cookies through `jar`, clones of the jar share their cookies.
+    #[cfg(feature = "cookies")]
+    pub fn set_cookie_jar(&mut self, jar: crate::cookies::Jar) -> Self {
+        self.cookies = Some(jar);
+        self.to_owned()
+    }
+    /// Records every request to a cassette, or answers them from one without a network.
+    #[cfg(feature = "vcr")]
+    pub fn set_vcr(&mut self, vcr: impl Into<Option<crate::vcr::Vcr>>) -> Self {
+        self.vcr = vcr.into();
+        self.to_owned()
+    }
+    /// Caches responses in `cache`, clones of the cache share their responses.
+    #[cfg(feature = "cache")]
+    pub fn set_cache(&mut self, cache: impl Into<Option<crate::cache::Cache>>) -> Self {
+        self.cache = cache.into();
+        self.to_owned()
+    }
+    /// Shares the response of a `GET` without a body with identical requests in flight.
+    #[cfg(feature = "single-flight")]
+    pub fn set_single_flight(
+        &mut self,
+        single_flight: impl Into<Option<crate::single_flight::SingleFlight<ReqwestBody>>>,
+    ) -> Self {
+        self.single_flight = single_flight.into();
+        self.to_owned()
+    }
+    /// Queues requests beyond the limits of `limit`, clones of the limit share their slots.
+    #[cfg(feature = "limit")]
+    pub fn set_limit(&mut self, limit: impl Into<Option<crate::limit::Limit>>) -> Self {
+        self.limit = limit.into();
+        self.to_owned()
+    }
+    /// Fails requests to hosts that keep failing with `CircuitOpen`, clones of the breaker
+    /// share their circuits.
+    #[cfg(feature = "circuit-breaker")]
+    pub fn set_circuit_breaker(
+        &mut self,
+        breaker: impl Into<Option<crate::breaker::Breaker>>,
+    ) -> Self {
+        self.breaker = breaker.into();
+        self.to_owned()
+    }
 }
 
 impl Reqwest {
@@ -81,14 +211,341 @@ impl Reqwest {
             .serde_switch()
     }
 
+    /// Waits for the `limit` slots of a request to `url`.
+    #[cfg(feature = "limit")]
+    pub(crate) async fn slot(&self, url: &str) -> Result<crate::engines::Slot> {
+        match &self.limit {
+            Some(limit) => Ok(Some(limit.acquire(url).await?)),
+            None => Ok(None),
+        }
+    }
+    #[cfg(not(feature = "limit"))]
+    #[allow(clippy::unused_async, clippy::unnecessary_wraps)]
+    pub(crate) async fn slot(&self, _url: &str) -> Result<crate::engines::Slot> {
+        Ok(None)
+    }
+
+    /// `history` receives the URLs that redirected on the way to the final response.
+    fn client(&self, history: &Arc<Mutex<Vec<String>>>) -> Result<Client> {
+        self.client_with(history, false)
+    }
+
+    /// A `WebSocket` is upgraded from HTTP/1.1, which is then the only version offered.
+    pub(crate) fn client_with(
+        &self,
+        history: &Arc<Mutex<Vec<String>>>,
+        websocket: bool,
+    ) -> Result<Client> {
+        let mut builder = Client::builder()
+            .http2_adaptive_window(self.http2.adaptive_window)
+            .http2_initial_stream_window_size(self.http2.initial_stream_window_size)
+            .http2_initial_connection_window_size(self.http2.initial_connection_window_size)
+            .http2_keep_alive_interval(self.http2.keep_alive_interval);
+
+        if websocket {
+            builder = builder.http1_only();
+        } else if self.http2.only {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        builder = builder.dns_resolver(Arc::new(self.dns.clone()));
+
+        if self.tcp.send_buffer_size.is_some() || self.tcp.recv_buffer_size.is_some() {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "reqwest can not set socket buffer sizes",
+            )));
+        }
+        if let Some(nodelay) = self.tcp.nodelay {
+            builder = builder.tcp_nodelay(nodelay);
+        }
+        let (v4, v6) = self.tcp.local_addresses()?;
+        builder = builder
+            .tcp_keepalive(self.tcp.keepalive)
+            .local_address(v4.map(IpAddr::V4).or_else(|| v6.map(IpAddr::V6)));
+
+        if let Some(proxy) = self.proxy.clone() {
+            builder = builder.no_proxy().proxy(reqwest::Proxy::custom(move |url| {
+                proxy.for_url(url).cloned()
+            }));
+        }
+
+        builder = self.tls_config(builder, websocket)?;
+        if let Some(timeout) = self.http2.keep_alive_timeout {
+            builder = builder.http2_keep_alive_timeout(timeout);
+        }
+
+        builder = builder.redirect(match self.redirect.clone() {
+            Policy::None => reqwest::redirect::Policy::none(),
+            policy => {
+                let history = history.clone();
+                reqwest::redirect::Policy::custom(move |attempt| {
+                    let previous = attempt
+                        .previous()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>();
+                    match policy.action(&Attempt {
+                        status: attempt.status().as_u16(),
+                        next: attempt.url().as_str(),
+                        previous: &previous,
+                    }) {
+                        Action::Follow => {
+                            if let Ok(mut history) = history.lock() {
+                                *history = previous;
+                            }
+                            attempt.follow()
+                        }
+                        Action::Stop => attempt.stop(),
+                        Action::Error => attempt.error(TooManyRedirects),
+                    }
+                })
+            }
+        });
+
+        #[cfg(feature = "cookies")]
+        if let Some(jar) = self.cookies.clone() {
+            builder = builder.cookie_provider(Arc::new(jar));
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Applies the TLS settings to `builder` with the backend selected through the features.
+    fn tls_config(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+        websocket: bool,
+    ) -> Result<reqwest::ClientBuilder> {
+        // rustls is preferred like in the hyper engine when both backends are enabled
+        #[cfg(feature = "reqwest-rustls")]
+        {
+            let mut config = self.tls.rustls_config()?;
+            config.alpn_protocols = if websocket {
+                vec![b"http/1.1".to_vec()]
+            } else if self.http2.only {
+                vec![b"h2".to_vec()]
+            } else {
+                vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+            };
+            builder = builder.use_preconfigured_tls(config);
+        }
+        #[cfg(all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls")))]
+        {
+            // reqwest offers ALPN protocols after `http1_only` on native-tls itself
+            let _ = websocket;
+            self.tls.native_tls_unsupported()?;
+            builder = builder
+                .tls_built_in_root_certs(!self.tls.no_system_roots)
+                .danger_accept_invalid_certs(self.tls.danger_accept_invalid_certs);
+
+            if let Some(version) = self.tls.min_version {
+                builder = builder.min_tls_version(match version {
+                    crate::tls::Version::Tls10 => reqwest::tls::Version::TLS_1_0,
+                    crate::tls::Version::Tls11 => reqwest::tls::Version::TLS_1_1,
+                    crate::tls::Version::Tls12 => reqwest::tls::Version::TLS_1_2,
+                    crate::tls::Version::Tls13 => reqwest::tls::Version::TLS_1_3,
+                });
+            }
+            for pem in self
+                .tls
+                .root_certificates
+                .iter()
+                .flat_map(|pem| crate::tls::pem_certificates(pem))
+            {
+                builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+            }
+            if let Some(identity) = &self.tls.identity {
+                builder = builder.identity(reqwest::Identity::from_pkcs8_pem(
+                    &identity.certificate_chain,
+                    &identity.private_key,
+                )?);
+            }
+        }
+        Ok(builder)
+    }
+
     pub async fn common(
         &self,
         method: Method,
         path: String,
         data: Option<Vec<u8>>,
+    ) -> Result<ReqwestBody> {
+        #[cfg(feature = "tracing")]
+        let span = crate::trace::span(
+            "reqwest",
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            data.as_ref().map_or(0, Vec::len),
+        );
+        #[cfg(feature = "metrics")]
+        let meter = crate::meter::Meter::new(
+            "reqwest",
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            data.as_ref().map_or(0, Vec::len),
+        );
+
+        #[cfg(feature = "circuit-breaker")]
+        let url = format!("{}{path}", self.url);
+
+        // boxed, every layer below would otherwise hold a copy of the request future
+        let response = Box::pin(async {
+            #[cfg(feature = "single-flight")]
+            if let Some(flight) = self
+                .single_flight
+                .as_ref()
+                .filter(|_| method == Method::GET && data.is_none())
+            {
+                let key = crate::single_flight::key(
+                    method.as_str(),
+                    &format!("{}{path}", self.url),
+                    &self.request_headers()?,
+                );
+                return flight.run(key, self.dispatch(method, path, data)).await;
+            }
+            self.dispatch(method, path, data).await
+        });
+
+        #[cfg(feature = "circuit-breaker")]
+        let response = crate::breaker::Breaker::call(
+            self.breaker.as_ref(),
+            &url,
+            response,
+            |body| body.status.as_u16(),
+            Error::is_failure,
+        );
+        #[cfg(feature = "metrics")]
+        let response = meter.measure(response);
+        #[cfg(feature = "tracing")]
+        return crate::trace::instrument(span, response).await;
+        #[cfg(not(feature = "tracing"))]
+        response.await
+    }
+
+    async fn dispatch(
+        &self,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<ReqwestBody> {
+        #[cfg(feature = "vcr")]
+        if let Some(vcr) = &self.vcr {
+            return self.cassette(vcr, method, path, data).await;
+        }
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &self.cache {
+            return self.cached(cache, method, path, data).await;
+        }
+        self.send(method, path, data).await
+    }
+
+    /// The headers sent with every request, as seen by the cassette and single-flight keys.
+    #[cfg(any(feature = "vcr", feature = "single-flight"))]
+    fn request_headers(&self) -> Result<HeaderMap> {
+        let mut headers = self.headers.clone();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(crate::USER_AGENT))?,
+        );
+        if let Some(content_type) = self.content_type.as_deref() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
+        }
+        Ok(headers)
+    }
+
+    #[cfg(feature = "cache")]
+    async fn cached(
+        &self,
+        cache: &crate::cache::Cache,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<ReqwestBody> {
+        let url = format!("{}{path}", self.url);
+        let (stale, body) = match cache.lookup(method.as_str(), &url, &self.headers) {
+            crate::cache::Lookup::Fresh(entry) => return Ok(entry.into()),
+            crate::cache::Lookup::Stale(entry, validators) => {
+                let mut client = self.clone();
+                client.headers.extend(validators);
+                (Some(entry), client.send(method.clone(), path, data).await?)
+            }
+            crate::cache::Lookup::Miss => (None, self.send(method.clone(), path, data).await?),
+        };
+
+        if let Some(entry) = stale.filter(|_| body.status == reqwest::StatusCode::NOT_MODIFIED) {
+            return Ok(cache
+                .revalidated(method.as_str(), &url, entry, &body.headers)
+                .into());
+        }
+        cache.store(
+            method.as_str(),
+            &url,
+            &self.headers,
+            body.status,
+            body.url.clone(),
+            &body.headers,
+            body.body.as_deref().unwrap_or_default(),
+        );
+        Ok(body)
+    }
+
+    #[cfg(feature = "vcr")]
+    async fn cassette(
+        &self,
+        vcr: &crate::vcr::Vcr,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<ReqwestBody> {
+        let request = vcr.request(
+            method.as_str(),
+            &format!("{}{path}", self.url),
+            &self.request_headers()?,
+            data.as_deref(),
+        );
+
+        match vcr.mode {
+            crate::vcr::Mode::Replay => {
+                let response = vcr.replay_response(&request)?;
+                Ok(ReqwestBody {
+                    body: response.body.bytes()?,
+                    status: response.status()?,
+                    headers: response.headers(),
+                    url: response.url,
+                    history: Vec::new(),
+                    version: reqwest::Version::HTTP_11,
+                })
+            }
+            crate::vcr::Mode::Record => {
+                let body = self.send(method, path, data).await?;
+                #[cfg(feature = "__compression")]
+                let decoded = !self.no_decompress;
+                #[cfg(not(feature = "__compression"))]
+                let decoded = false;
+                vcr.record_response(
+                    request,
+                    body.status,
+                    &body.url,
+                    &body.headers,
+                    body.body.as_deref(),
+                    decoded,
+                )?;
+                Ok(body)
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: String,
+        data: Option<Vec<u8>>,
     ) -> Result<ReqwestBody> {
         let mut request = Request::new(method, Url::from_str(&format!("{}{path}", self.url))?);
-        let client = Client::new();
+        // held until the response body is read, across redirects
+        let _slot = self.slot(request.url().as_str()).await?;
+        let history = Arc::default();
+        let client = self.client(&history)?;
 
         let headers = request.headers_mut();
 
@@ -101,13 +558,68 @@ impl Reqwest {
         if let Some(content_type) = self.content_type.as_deref() {
             headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type)?);
         }
+
+        // reqwest only decodes the encodings of its own features, this covers all of ours
+        #[cfg(feature = "__compression")]
+        headers
+            .entry(reqwest::header::ACCEPT_ENCODING)
+            .or_insert(HeaderValue::from_str(
+                &crate::compression::accept_encoding(),
+            )?);
+
+        #[cfg(feature = "__compression")]
+        let data = match (self.body_encoding, data) {
+            (Some(encoding), Some(body)) => {
+                headers.insert(
+                    reqwest::header::CONTENT_ENCODING,
+                    HeaderValue::from_static(encoding.as_str()),
+                );
+                Some(encoding.encode(&body)?)
+            }
+            (_, data) => data,
+        };
+
         *request.body_mut() = data.map(Body::from);
 
+        #[cfg(feature = "__compression")]
+        let sent = request.method().clone();
         let response = client.execute(request).await?;
         let status = response.status();
+        let response_headers = response.headers().clone();
+        let url = response.url().to_string();
+        let version = response.version();
+        let history = history
+            .lock()
+            .map(|history| history.clone())
+            .unwrap_or_default();
 
-        let body = response.bytes().await?.to_vec().into();
-        Ok(ReqwestBody { body, status })
+        #[cfg(feature = "__compression")]
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|encoding| encoding.to_str().ok())
+            .filter(|_| {
+                !self.no_decompress && crate::compression::has_body(sent.as_str(), status.as_u16())
+            })
+            .map(ToOwned::to_owned);
+
+        let body = response.bytes().await?.to_vec();
+
+        #[cfg(feature = "__compression")]
+        let body = match content_encoding {
+            Some(encoding) => crate::compression::decode(&encoding, body)?,
+            None => body,
+        };
+
+        let body = body.into();
+        Ok(ReqwestBody {
+            body,
+            status,
+            headers: response_headers,
+            url,
+            history,
+            version,
+        })
     }
 
     pub async fn request_raw(
@@ -117,14 +629,203 @@ impl Reqwest {
         data: impl Into<Option<Vec<u8>>>,
     ) -> Result<Vec<u8>> {
         Ok(self
-            .common(
-                method.into(),
-                format!("{}{}", self.url, path.into()),
-                data.into(),
-            )
+            .common(method.into(), path.into(), data.into())
             .await?
             .body
-            .get_or_insert_with(Vec::new)
-            .to_vec())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "sse")]
+impl Reqwest {
+    /// Opens `path` as a `text/event-stream` once the first event is awaited.
+    #[must_use]
+    pub fn event_source(&self, path: impl Into<String>) -> crate::sse::EventSource<Self> {
+        crate::sse::EventSource::new(self.clone(), path.into())
+    }
+}
+
+#[cfg(feature = "sse")]
+impl crate::sse::Connect for Reqwest {
+    type Response = Held<reqwest::Response>;
+    type Error = Error;
+
+    async fn connect(
+        &self,
+        path: &str,
+        last_event_id: Option<&str>,
+    ) -> Result<Option<Held<reqwest::Response>>> {
+        let url = format!("{}{path}", self.url);
+        let slot = self.slot(&url).await?;
+        let mut request = self
+            .client(&Arc::default())?
+            .get(url)
+            .headers(self.headers.clone())
+            .header(
+                USER_AGENT,
+                self.user_agent.as_deref().unwrap_or(crate::USER_AGENT),
+            )
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .header(reqwest::header::CACHE_CONTROL, "no-cache");
+        if let Some(id) = last_event_id {
+            request = request.header("last-event-id", id);
+        }
+
+        let response = request.send().await?;
+        match response.status() {
+            reqwest::StatusCode::OK => Ok(Some(Held::new(response, slot))),
+            reqwest::StatusCode::NO_CONTENT => Ok(None),
+            status => Err(Error::StatusCode(status)),
+        }
+    }
+
+    async fn chunk(response: &mut Held<reqwest::Response>) -> Option<Vec<u8>> {
+        response
+            .chunk()
+            .await
+            .ok()
+            .flatten()
+            .map(|chunk| chunk.to_vec())
+    }
+}
+
+#[cfg(feature = "poll")]
+impl Reqwest {
+    /// Polls `path` with `GET` once the first body is awaited.
+    #[must_use]
+    pub fn poll(&self, path: impl Into<String>) -> crate::poll::Poller<Self> {
+        crate::poll::Poller::new(self.clone(), path.into())
+    }
+}
+
+#[cfg(feature = "poll")]
+impl crate::poll::Fetch for Reqwest {
+    type Error = Error;
+
+    async fn fetch(&self, path: &str, etag: Option<&str>) -> Result<Option<crate::poll::Fetched>> {
+        let mut client = self.clone();
+        if let Some(etag) = etag {
+            client
+                .headers
+                .insert(reqwest::header::IF_NONE_MATCH, HeaderValue::from_str(etag)?);
+        }
+
+        let response = client.common(Method::GET, path.to_owned(), None).await?;
+        match response.status {
+            reqwest::StatusCode::OK => Ok(Some(crate::poll::Fetched {
+                etag: response
+                    .headers
+                    .get(reqwest::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_owned),
+                body: response.body.unwrap_or_default(),
+            })),
+            reqwest::StatusCode::NOT_MODIFIED => Ok(None),
+            status => Err(Error::StatusCode(status)),
+        }
+    }
+}
+
+#[cfg(feature = "paginate")]
+impl Reqwest {
+    /// Pages through `path` with `GET` once the first item is awaited.
+    #[must_use]
+    pub fn paginate<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        next: crate::paginate::Next,
+    ) -> crate::paginate::Paginator<Self, T> {
+        crate::paginate::Paginator::new(self.clone(), path, next)
+    }
+}
+
+#[cfg(feature = "paginate")]
+impl crate::paginate::Get for Reqwest {
+    type Error = Error;
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.url)
+    }
+
+    async fn get(&self, url: &str) -> Result<crate::paginate::Response> {
+        // the next pages are absolute URLs
+        let mut client = self.clone();
+        client.url = String::new();
+
+        let response = client.common(Method::GET, url.to_owned(), None).await?;
+        if response.status != reqwest::StatusCode::OK {
+            return Err(Error::StatusCode(response.status));
+        }
+        Ok(crate::paginate::Response {
+            link: response
+                .headers
+                .get(reqwest::header::LINK)
+                .and_then(|link| link.to_str().ok())
+                .map(str::to_owned),
+            body: response.body.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(feature = "download")]
+impl Reqwest {
+    /// Streams `path` to `file`, returning the size of the file.
+    pub async fn download_to(
+        &self,
+        path: &str,
+        file: impl AsRef<std::path::Path>,
+        download: &crate::download::Download,
+    ) -> Result<u64> {
+        download.run(self, path, file.as_ref()).await
+    }
+}
+
+#[cfg(feature = "download")]
+impl crate::download::Open for Reqwest {
+    type Response = Held<reqwest::Response>;
+    type Error = Error;
+
+    async fn open(
+        &self,
+        path: &str,
+        resume: Option<(u64, &str)>,
+    ) -> Result<(crate::download::Head, Held<reqwest::Response>)> {
+        let url = format!("{}{path}", self.url);
+        let slot = self.slot(&url).await?;
+        let mut request = self
+            .client(&Arc::default())?
+            .get(url)
+            .headers(self.headers.clone())
+            .header(
+                USER_AGENT,
+                self.user_agent.as_deref().unwrap_or(crate::USER_AGENT),
+            )
+            // ranges are counted in bytes of the file as stored
+            .header(reqwest::header::ACCEPT_ENCODING, "identity");
+        if let Some((offset, validator)) = resume {
+            request = request
+                .header(reqwest::header::RANGE, format!("bytes={offset}-"))
+                .header(reqwest::header::IF_RANGE, validator);
+        }
+
+        let response = request.send().await?;
+        match response.status().as_u16() {
+            200 | 206 => {}
+            416 if resume.is_some() => {}
+            _ => return Err(Error::StatusCode(response.status())),
+        }
+        let head = crate::download::Head::new(response.status().as_u16(), |name| {
+            response.headers().get(name)?.to_str().ok()
+        });
+        Ok((head, Held::new(response, slot)))
+    }
+
+    async fn chunk(response: &mut Held<reqwest::Response>) -> Option<Result<Vec<u8>>> {
+        response
+            .chunk()
+            .await
+            .map_err(Error::from)
+            .transpose()
+            .map(|chunk| chunk.map(|chunk| chunk.to_vec()))
     }
 }
@@ -1,6 +1,8 @@
 pub mod core;
 pub mod results;
 pub mod tests;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 // re-exports
 pub use crate::engines::reqwest::results::Error;
@@ -15,10 +17,43 @@ pub struct Reqwest {
     pub user_agent: Option<String>,
     pub content_type: Option<String>,
     pub headers: reqwest::header::HeaderMap,
+    pub redirect: crate::redirect::Policy,
+    pub http2: crate::http2::Http2,
+    pub proxy: Option<crate::proxy::Proxy>,
+    pub dns: crate::dns::Dns,
+    pub tcp: crate::tcp::Tcp,
+    #[cfg(feature = "__tls")]
+    pub tls: crate::tls::Tls,
+    /// Keeps compressed response bodies as received, `Accept-Encoding` is still sent.
+    #[cfg(feature = "__compression")]
+    pub no_decompress: bool,
+    /// Compresses request bodies and sets `Content-Encoding` accordingly.
+    #[cfg(feature = "__compression")]
+    pub body_encoding: Option<crate::compression::Encoding>,
+    #[cfg(feature = "cookies")]
+    pub cookies: Option<crate::cookies::Jar>,
+    #[cfg(feature = "vcr")]
+    pub vcr: Option<crate::vcr::Vcr>,
+    #[cfg(feature = "cache")]
+    pub cache: Option<crate::cache::Cache>,
+    #[cfg(feature = "single-flight")]
+    pub single_flight: Option<crate::single_flight::SingleFlight<ReqwestBody>>,
+    #[cfg(feature = "limit")]
+    pub limit: Option<crate::limit::Limit>,
+    #[cfg(feature = "circuit-breaker")]
+    pub breaker: Option<crate::breaker::Breaker>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ReqwestBody {
     pub body: Option<Vec<u8>>,
     pub status: StatusCode,
+    /// The headers of the final response.
+    pub headers: HeaderMap,
+    /// The URL of the final response, after following redirects.
+    pub url: String,
+    /// Every URL that redirected before reaching `url`, in order.
+    pub history: Vec<String>,
+    /// The HTTP version negotiated for the final response.
+    pub version: reqwest::Version,
 }
@@ -10,6 +10,26 @@ pub enum Error {
     HeaderValue(InvalidHeaderValue),
     HeaderName(InvalidHeaderName),
     StatusCode(StatusCode),
+    Io(std::io::Error),
+    /// Replay found no recorded exchange for the request.
+    #[cfg(feature = "vcr")]
+    Vcr(crate::vcr::Unmatched),
+    /// Too many requests were waiting for a slot of the [`Limit`](crate::limit::Limit).
+    #[cfg(feature = "limit")]
+    QueueFull(crate::limit::QueueFull),
+    /// The circuit of the host is open after too many failed requests.
+    #[cfg(feature = "circuit-breaker")]
+    CircuitOpen(crate::breaker::CircuitOpen),
+    /// Boxed like in the hyper engine.
+    #[cfg(feature = "websocket")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    #[cfg(feature = "msgpack")]
+    MsgPackEncode(rmp_serde::encode::Error),
+    #[cfg(feature = "msgpack")]
+    MsgPackDecode(rmp_serde::decode::Error),
+    /// The downloaded file is not the one expected.
+    #[cfg(feature = "download")]
+    Download(crate::download::Mismatch),
 }
 pub type Result<T> = std::result::Result<T, Error>;
 impl From<reqwest::Error> for Error {
@@ -42,3 +62,72 @@ impl From<serde_json::Error> for Error {
         Self::Serde(value)
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[cfg(feature = "vcr")]
+impl From<crate::vcr::Unmatched> for Error {
+    fn from(value: crate::vcr::Unmatched) -> Self {
+        Self::Vcr(value)
+    }
+}
+
+#[cfg(feature = "limit")]
+impl From<crate::limit::QueueFull> for Error {
+    fn from(value: crate::limit::QueueFull) -> Self {
+        Self::QueueFull(value)
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+impl From<crate::breaker::CircuitOpen> for Error {
+    fn from(value: crate::breaker::CircuitOpen) -> Self {
+        Self::CircuitOpen(value)
+    }
+}
+
+#[cfg(feature = "circuit-breaker")]
+impl Error {
+    /// The host could not be reached or answered with a server error, counted by the
+    /// circuit breaker. Other errors, such as an invalid request, are left uncounted.
+    pub(crate) fn is_failure(&self) -> bool {
+        match self {
+            Self::Engine(error) => !error.is_builder(),
+            Self::Io(_) => true,
+            Self::StatusCode(status) => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "download")]
+impl From<crate::download::Mismatch> for Error {
+    fn from(value: crate::download::Mismatch) -> Self {
+        Self::Download(value)
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(value: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(value))
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(value: rmp_serde::encode::Error) -> Self {
+        Self::MsgPackEncode(value)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(value: rmp_serde::decode::Error) -> Self {
+        Self::MsgPackDecode(value)
+    }
+}
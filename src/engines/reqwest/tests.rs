@@ -1,13 +1,204 @@
 #[cfg(test)]
 mod tests {
-    use crate::engines::reqwest::Reqwest;
+    use crate::{
+        dns::Dns,
+        engines::reqwest::Reqwest,
+        test_util::{respond, Server},
+    };
     use reqwest::Method;
 
     #[tokio::test]
-    async fn request_basic() {
-        assert!(Reqwest::new()
-            .request_raw(Method::GET, "https://api.revolt.chat", None)
-            .await
-            .is_ok());
+    async fn request_basic() -> crate::engines::reqwest::results::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/", |_| respond(200, "revolt"));
+
+        assert_eq!(
+            Reqwest::new()
+                .set_url(server.url())
+                .request_raw(Method::GET, "/", None)
+                .await?,
+            b"revolt"
+        );
+        assert_eq!(
+            server.received()[0].headers["user-agent"],
+            crate::USER_AGENT
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn request_serde() -> crate::engines::reqwest::results::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users/@me", |_| {
+            respond(200, r#"{"_id":"01"}"#)
+        });
+
+        assert_eq!(
+            Reqwest::new()
+                .set_url(server.url())
+                .request::<serde_json::Value>(Method::GET, "/users/@me", None)
+                .await?,
+            serde_json::json!({"_id": "01"})
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn request_gzip_without_body() -> crate::engines::reqwest::results::Result<()> {
+        let gzip = |status| {
+            let mut response = respond(status, Vec::new());
+            response.headers_mut().insert(
+                reqwest::header::CONTENT_ENCODING,
+                reqwest::header::HeaderValue::from_static("gzip"),
+            );
+            response
+        };
+        let server = Server::start().await?;
+        server
+            .route(Method::HEAD, "/", move |_| gzip(200))
+            .route(Method::GET, "/empty", move |_| gzip(204))
+            .route(Method::GET, "/cached", move |_| gzip(304));
+        let client = Reqwest::new().set_url(server.url());
+
+        // the encoding describes a body that is not sent
+        for (method, path, status) in [
+            (Method::HEAD, "/", 200),
+            (Method::GET, "/empty", 204),
+            (Method::GET, "/cached", 304),
+        ] {
+            let response = client.common(method, String::from(path), None).await?;
+            assert_eq!(response.status.as_u16(), status);
+            assert_eq!(response.body, Some(Vec::new()));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "reqwest-rustls")]
+    #[tokio::test]
+    async fn request_http2() -> crate::engines::reqwest::results::Result<()> {
+        use crate::{http2::Http2, test_util::CERTIFICATE, tls::Tls};
+        use reqwest::Version;
+
+        // negotiated through ALPN
+        let server = Server::start_tls().await?;
+        server.route(Method::GET, "/", |_| respond(200, "ok"));
+        let response = Reqwest::new()
+            .set_url(server.url())
+            .set_tls(
+                Tls::new()
+                    .set_system_roots(false)
+                    .add_root_certificate(CERTIFICATE),
+            )
+            .common(Method::GET, String::from("/"), None)
+            .await?;
+        assert_eq!(response.version, Version::HTTP_2);
+
+        // with prior knowledge over plain TCP
+        let server = Server::start().await?;
+        server.route(Method::GET, "/", |_| respond(200, "ok"));
+        let response = Reqwest::new()
+            .set_url(server.url())
+            .set_http2(Http2::only())
+            .common(Method::GET, String::from("/"), None)
+            .await?;
+        assert_eq!(response.version, Version::HTTP_2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_dns_override() -> crate::engines::reqwest::results::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::GET, "/users", |_| respond(200, "ok"));
+        let address = server.address();
+
+        assert_eq!(
+            Reqwest::new()
+                .set_dns(Dns::new().add_override("api.example.invalid", [address.ip()]))
+                .request_raw(
+                    Method::GET,
+                    format!("http://api.example.invalid:{}/users", address.port()),
+                    None,
+                )
+                .await?,
+            b"ok"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "cookies")]
+    #[tokio::test]
+    async fn request_cookies() -> crate::engines::reqwest::results::Result<()> {
+        let server = Server::start().await?;
+        server.route(Method::POST, "/auth/login", |_| {
+            let mut response = respond(303, Vec::new());
+            let headers = response.headers_mut();
+            headers.insert(
+                "set-cookie",
+                reqwest::header::HeaderValue::from_static("session=abc; Path=/"),
+            );
+            headers.insert(
+                "location",
+                reqwest::header::HeaderValue::from_static("/users/@me"),
+            );
+            response
+        });
+        server.route(Method::GET, "/users/@me", |_| respond(200, "{}"));
+        let jar = crate::cookies::Jar::new();
+        let client = Reqwest::new()
+            .set_url(server.url())
+            .set_cookie_jar(jar.clone());
+
+        // the cookie set by the redirect is sent to its target
+        client
+            .request_raw(Method::POST, "/auth/login", None)
+            .await?;
+        client.request_raw(Method::GET, "/users/@me", None).await?;
+        let received = server.received();
+        assert!(!received[0].headers.contains_key("cookie"));
+        assert_eq!(received[1].headers["cookie"], "session=abc");
+        assert_eq!(received[2].headers["cookie"], "session=abc");
+        assert!(jar.cookie_header(&server.url().parse()?).is_some());
+        Ok(())
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn request_websocket() -> crate::engines::reqwest::results::Result<()> {
+        use crate::websocket::Message;
+        use futures_util::{SinkExt, StreamExt};
+
+        // echoes messages
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let Ok(mut socket) = tokio_tungstenite::accept_async(stream).await else {
+                return;
+            };
+            while let Some(Ok(message)) = socket.next().await {
+                let _ = socket.send(message).await;
+            }
+        });
+
+        let mut socket = Reqwest::new()
+            .set_url(format!("ws://{address}"))
+            .websocket("/");
+        socket.greeting = vec![Message::Text(String::from("hello"))];
+        assert_eq!(
+            socket.next().await.transpose()?,
+            Some(Message::Text(String::from("hello")))
+        );
+        socket.send(Message::Binary(vec![1, 2])).await?;
+        assert_eq!(
+            socket.next().await.transpose()?,
+            Some(Message::Binary(vec![1, 2]))
+        );
+        socket.close().await;
+        drop(socket);
+        Ok(())
     }
 }
@@ -0,0 +1,121 @@
+use crate::{
+    engines::{
+        reqwest::{results::Result, Error, Reqwest},
+        Held,
+    },
+    websocket::{Connect, Incoming, Message, WebSocket},
+};
+use futures_util::{SinkExt, StreamExt};
+use reqwest::{
+    header::{
+        HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION,
+        UPGRADE, USER_AGENT,
+    },
+    StatusCode, Upgraded,
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        self,
+        error::ProtocolError,
+        handshake::{client::generate_key, derive_accept_key},
+        protocol::Role,
+    },
+    WebSocketStream,
+};
+
+/// A WebSocket over a connection upgraded by reqwest, with the proxy, TLS and cookie settings
+/// of the client.
+pub type Socket = Held<WebSocketStream<Upgraded>>;
+
+impl Reqwest {
+    /// `path` is appended to the URL, `ws://` and `wss://` as well as `http://` and `https://`
+    /// URLs are accepted.
+    #[must_use]
+    pub fn websocket(&self, path: impl Into<String>) -> WebSocket<Self> {
+        WebSocket::new(self.clone(), path.into())
+    }
+}
+
+impl Connect for Reqwest {
+    type Socket = Socket;
+    type Error = Error;
+
+    async fn connect(&self, path: &str) -> Result<Socket> {
+        // reqwest only sends `http://` and `https://` requests, the upgrade is asked for in them
+        let url = format!("{}{path}", self.url);
+        let url = match url.split_once("://") {
+            Some(("wss", rest)) => format!("https://{rest}"),
+            Some(("ws", rest)) => format!("http://{rest}"),
+            _ => url,
+        };
+        // held for as long as the socket is open
+        let slot = self.slot(&url).await?;
+
+        let key = generate_key();
+        let response = self
+            .client_with(&std::sync::Arc::default(), true)?
+            .get(url)
+            .headers(self.headers.clone())
+            .header(
+                USER_AGENT,
+                HeaderValue::from_str(self.user_agent.as_deref().unwrap_or(crate::USER_AGENT))?,
+            )
+            .header(CONNECTION, "Upgrade")
+            .header(UPGRADE, "websocket")
+            .header(SEC_WEBSOCKET_VERSION, "13")
+            .header(SEC_WEBSOCKET_KEY, key.as_str())
+            .send()
+            .await?;
+
+        if response.status() != StatusCode::SWITCHING_PROTOCOLS {
+            return Err(Error::StatusCode(response.status()));
+        }
+        let accept = derive_accept_key(key.as_bytes());
+        if response.headers().get(SEC_WEBSOCKET_ACCEPT) != Some(&HeaderValue::from_str(&accept)?) {
+            return Err(
+                tungstenite::Error::Protocol(ProtocolError::SecWebSocketAcceptKeyMismatch).into(),
+            );
+        }
+        let socket =
+            WebSocketStream::from_raw_socket(response.upgrade().await?, Role::Client, None).await;
+        Ok(Held::new(socket, slot))
+    }
+
+    async fn send(socket: &mut Socket, message: Message) -> Result<()> {
+        Ok(socket
+            .send(match message {
+                Message::Text(text) => tungstenite::Message::Text(text),
+                Message::Binary(bytes) => tungstenite::Message::Binary(bytes),
+            })
+            .await?)
+    }
+
+    async fn receive(socket: &mut Socket) -> Option<Result<Incoming>> {
+        loop {
+            return match socket.next().await? {
+                Ok(tungstenite::Message::Text(text)) => {
+                    Some(Ok(Incoming::Message(Message::Text(text))))
+                }
+                Ok(tungstenite::Message::Binary(bytes)) => {
+                    Some(Ok(Incoming::Message(Message::Binary(bytes))))
+                }
+                Ok(tungstenite::Message::Pong(_)) => Some(Ok(Incoming::Pong)),
+                Ok(tungstenite::Message::Close(_))
+                | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    None
+                }
+                // pings are answered while reading
+                Ok(_) => continue,
+                Err(error) => Some(Err(error.into())),
+            };
+        }
+    }
+
+    async fn ping(socket: &mut Socket) -> Result<()> {
+        Ok(socket.send(tungstenite::Message::Ping(Vec::new())).await?)
+    }
+
+    async fn close(socket: &mut Socket) {
+        let _ = socket.close(None).await;
+    }
+}
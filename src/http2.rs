@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+/// HTTP/2 settings of the native engines, HTTP/2 is otherwise used whenever ALPN negotiates it.
+///
+/// The maximum of concurrent streams is announced by the server through its `SETTINGS` frame,
+/// neither hyper nor reqwest let the client lower it. Every request opens its own connection, so
+/// requests are not multiplexed over a shared one either, `Limit::max_per_host` of the `limit`
+/// feature caps the connections to a host.
+#[derive(Debug, Clone, Default)]
+pub struct Http2 {
+    /// Only speaks HTTP/2, requested through ALPN over TLS and with prior knowledge (h2c) over
+    /// plain `http://` URLs, which suits local services.
+    pub only: bool,
+    /// Sizes the flow control windows from the measured bandwidth-delay product, this overrides
+    /// the window sizes below.
+    pub adaptive_window: bool,
+    pub initial_stream_window_size: Option<u32>,
+    pub initial_connection_window_size: Option<u32>,
+    /// Interval of `PING` frames keeping the connection alive.
+    pub keep_alive_interval: Option<Duration>,
+    /// How long to wait for a `PING` acknowledgement before closing the connection.
+    pub keep_alive_timeout: Option<Duration>,
+}
+
+impl Http2 {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// HTTP/2 with prior knowledge, see [`Http2::only`].
+    #[must_use]
+    pub fn only() -> Self {
+        Self {
+            only: true,
+            ..Self::default()
+        }
+    }
+}
@@ -7,7 +7,130 @@
     clippy::expect_used
 )]
 
+#[cfg(all(
+    feature = "circuit-breaker",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod breaker;
+#[cfg(all(
+    feature = "cache",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub mod cache;
+#[cfg(feature = "__compression")]
+pub mod compression;
+#[cfg(feature = "cookies")]
+pub mod cookies;
+#[cfg(any(feature = "__hyper", feature = "__reqwest"))]
+pub mod dns;
+#[cfg(all(
+    feature = "download",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub mod download;
 pub mod engines;
+#[cfg(any(feature = "__hyper", feature = "__reqwest"))]
+pub mod http2;
+#[cfg(all(
+    feature = "limit",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub mod limit;
+#[cfg(all(
+    feature = "metrics",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod meter;
+#[cfg(all(
+    any(feature = "tracing", feature = "metrics", feature = "circuit-breaker"),
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+mod observe;
+#[cfg(all(
+    feature = "paginate",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod paginate;
+#[cfg(all(
+    feature = "poll",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod poll;
+#[cfg(any(feature = "__hyper", feature = "__reqwest"))]
+pub mod proxy;
+pub mod redirect;
+#[cfg(all(
+    feature = "single-flight",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub mod single_flight;
+#[cfg(all(
+    feature = "sse",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod sse;
+#[cfg(any(feature = "__hyper", feature = "__reqwest"))]
+pub mod tcp;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(all(
+    any(feature = "sse", feature = "poll", feature = "websocket"),
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+mod timer;
+#[cfg(feature = "__tls")]
+pub mod tls;
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+mod trace;
 pub mod utils;
+#[cfg(all(
+    feature = "vcr",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub mod vcr;
+#[cfg(all(
+    feature = "websocket",
+    any(
+        feature = "__hyper",
+        feature = "__reqwest",
+        feature = "reqwasm_engine"
+    )
+))]
+pub mod websocket;
 
 pub const USER_AGENT: &str = "Reywen-HTTP/10.0 (async-tokio-runtime)";
@@ -0,0 +1,223 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Caps the requests an engine has in flight, in total and per host. Further requests wait in
+/// a queue for a slot to free up.
+///
+/// Clones share their slots and queue, set the limits before the first request.
+#[derive(Debug, Clone, Default)]
+pub struct Limit {
+    /// Requests in flight across all hosts, unlimited when unset. Zero fails every request with
+    /// [`QueueFull`].
+    pub max_in_flight: Option<usize>,
+    /// Requests in flight to a single host and port, unlimited when unset. Zero fails every
+    /// request with [`QueueFull`].
+    pub max_per_host: Option<usize>,
+    /// Requests waiting for a slot, beyond it they fail with [`QueueFull`] instead.
+    pub max_queued: Option<usize>,
+    state: Arc<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    client: Mutex<Option<Arc<Semaphore>>>,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+    queued: AtomicUsize,
+}
+
+/// The queue of a [`Limit`] was full or it allows no requests, the request was not sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFull;
+
+impl Display for QueueFull {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "too many requests queued")
+    }
+}
+
+impl std::error::Error for QueueFull {}
+
+/// The slots of a request, released when dropped.
+#[derive(Debug)]
+pub(crate) struct Permit {
+    _host: Option<OwnedSemaphorePermit>,
+    _client: Option<OwnedSemaphorePermit>,
+}
+
+impl Limit {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_max_in_flight(&mut self, max: impl Into<Option<usize>>) -> Self {
+        self.max_in_flight = max.into();
+        self.to_owned()
+    }
+    pub fn set_max_per_host(&mut self, max: impl Into<Option<usize>>) -> Self {
+        self.max_per_host = max.into();
+        self.to_owned()
+    }
+    /// Zero fails every request that can not be sent right away.
+    pub fn set_max_queued(&mut self, max: impl Into<Option<usize>>) -> Self {
+        self.max_queued = max.into();
+        self.to_owned()
+    }
+
+    /// Requests waiting for a slot.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.state.queued.load(Ordering::Relaxed)
+    }
+
+    /// Waits for a slot to send a request to `url`.
+    pub(crate) async fn acquire(&self, url: &str) -> Result<Permit, QueueFull> {
+        // no slot would ever free up
+        if self.max_in_flight == Some(0) || self.max_per_host == Some(0) {
+            return Err(QueueFull);
+        }
+        let client = self.max_in_flight.map(|max| {
+            self.state
+                .client
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert_with(|| semaphore(max))
+                .clone()
+        });
+        let host = self.max_per_host.map(|max| {
+            let mut hosts = self
+                .state
+                .hosts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            // only the map holds the semaphore of a host without requests in flight or queued
+            hosts.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+            hosts
+                .entry(host(url))
+                .or_insert_with(|| semaphore(max))
+                .clone()
+        });
+
+        // the host slot first, a client slot is not held while another host is busy
+        let mut queued = None;
+        Ok(Permit {
+            _host: self.wait(host, &mut queued).await?,
+            _client: self.wait(client, &mut queued).await?,
+        })
+    }
+
+    async fn wait(
+        &self,
+        semaphore: Option<Arc<Semaphore>>,
+        queued: &mut Option<Queued>,
+    ) -> Result<Option<OwnedSemaphorePermit>, QueueFull> {
+        let Some(semaphore) = semaphore else {
+            return Ok(None);
+        };
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Ok(Some(permit));
+        }
+        if queued.is_none() {
+            *queued = Some(self.enqueue()?);
+        }
+        // the semaphores are never closed
+        Ok(semaphore.acquire_owned().await.ok())
+    }
+
+    fn enqueue(&self) -> Result<Queued, QueueFull> {
+        let max = self.max_queued.unwrap_or(usize::MAX);
+        self.state
+            .queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |queued| {
+                (queued < max).then_some(queued + 1)
+            })
+            .map_err(|_| QueueFull)?;
+        Ok(Queued(self.state.clone()))
+    }
+}
+
+/// Counts a request as queued until dropped, also when the request future is.
+struct Queued(Arc<State>);
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        self.0.queued.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Limits beyond what a semaphore can count are clamped, as good as unlimited.
+fn semaphore(max: usize) -> Arc<Semaphore> {
+    Arc::new(Semaphore::new(max.min(Semaphore::MAX_PERMITS)))
+}
+
+/// `host:port` of `url`, or the whole URL when it can not be parsed.
+fn host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            Some(format!(
+                "{}:{}",
+                parsed.host_str()?,
+                parsed.port_or_known_default()?
+            ))
+        })
+        .unwrap_or_else(|| url.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limit::{Limit, QueueFull};
+
+    #[tokio::test]
+    async fn queue_and_hosts() -> Result<(), QueueFull> {
+        let limit = Limit::new().set_max_per_host(1).set_max_queued(1);
+        let first = limit.acquire("https://api.revolt.chat/users").await?;
+        // other hosts have their own slots
+        let other = limit.acquire("https://autumn.revolt.chat/").await?;
+
+        let waiting = {
+            let limit = limit.clone();
+            tokio::spawn(async move { limit.acquire("https://api.revolt.chat:443/").await })
+        };
+        while limit.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            limit.acquire("https://api.revolt.chat/").await.err(),
+            Some(QueueFull)
+        );
+
+        drop(first);
+        assert!(matches!(waiting.await, Ok(Ok(_))));
+        assert_eq!(limit.queued(), 0);
+
+        // idle hosts are forgotten
+        drop(other);
+        let _third = limit.acquire("https://cdn.revoltusercontent.com/").await?;
+        assert_eq!(
+            limit.state.hosts.lock().map(|hosts| hosts.len()).ok(),
+            Some(1)
+        );
+
+        let _unlimited = Limit::new()
+            .set_max_in_flight(usize::MAX)
+            .acquire("https://api.revolt.chat/")
+            .await?;
+        assert_eq!(
+            Limit::new()
+                .set_max_per_host(0)
+                .acquire("https://api.revolt.chat/")
+                .await
+                .err(),
+            Some(QueueFull)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use crate::observe::{now, server, Observed};
+use metrics::{
+    decrement_gauge, describe_counter, describe_gauge, describe_histogram, increment_gauge, Label,
+    Unit,
+};
+use std::future::Future;
+
+pub const REQUESTS: &str = "http_client_requests_total";
+pub const DURATION: &str = "http_client_request_duration_seconds";
+pub const IN_FLIGHT: &str = "http_client_requests_in_flight";
+pub const REDIRECTS: &str = "http_client_redirects_total";
+pub const SENT_BYTES: &str = "http_client_request_body_bytes_total";
+pub const RECEIVED_BYTES: &str = "http_client_response_body_bytes_total";
+
+/// Registers the descriptions of the metrics with the installed recorder, for `# HELP` lines.
+pub fn describe() {
+    describe_counter!(
+        REQUESTS,
+        Unit::Count,
+        "Completed requests by engine, method, host and status class."
+    );
+    describe_histogram!(
+        DURATION,
+        Unit::Seconds,
+        "Time until the response body was read."
+    );
+    describe_gauge!(
+        IN_FLIGHT,
+        Unit::Count,
+        "Requests waiting for their response."
+    );
+    describe_counter!(REDIRECTS, Unit::Count, "Redirects followed.");
+    describe_counter!(SENT_BYTES, Unit::Bytes, "Request body bytes sent.");
+    describe_counter!(RECEIVED_BYTES, Unit::Bytes, "Response body bytes received.");
+}
+
+/// Labels of a request, recorded once it completes.
+pub(crate) struct Meter {
+    labels: Vec<Label>,
+    body_size: usize,
+}
+
+impl Meter {
+    pub(crate) fn new(engine: &'static str, method: &str, url: &str, body_size: usize) -> Self {
+        Self {
+            labels: vec![
+                Label::new("engine", engine),
+                Label::new("method", method.to_owned()),
+                Label::new("host", server(url).0.to_owned()),
+            ],
+            body_size,
+        }
+    }
+
+    /// Runs `request` and records its count, duration and transferred bytes.
+    pub(crate) async fn measure<T: Observed, E>(
+        self,
+        request: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let start = now();
+        let result = {
+            let _in_flight = InFlight::new(&self.labels);
+            request.await
+        };
+        let seconds = (now() - start) / 1000.0;
+
+        let status_class = result.as_ref().map_or_else(
+            |_| String::from("error"),
+            |response| format!("{}xx", response.status() / 100),
+        );
+        let mut labels = self.labels.clone();
+        labels.push(Label::new("status_class", status_class));
+
+        metrics::counter!(REQUESTS, 1, labels.clone());
+        metrics::histogram!(DURATION, seconds, labels);
+        metrics::counter!(SENT_BYTES, self.body_size as u64, self.labels.clone());
+        if let Ok(response) = &result {
+            metrics::counter!(
+                REDIRECTS,
+                response.redirect_count() as u64,
+                self.labels.clone()
+            );
+            metrics::counter!(RECEIVED_BYTES, response.body_size() as u64, self.labels);
+        }
+        result
+    }
+}
+
+/// Counts a request as in flight until dropped, also when the request future is.
+struct InFlight(Vec<Label>);
+
+impl InFlight {
+    fn new(labels: &[Label]) -> Self {
+        increment_gauge!(IN_FLIGHT, 1.0, labels.to_vec());
+        Self(labels.to_vec())
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        decrement_gauge!(IN_FLIGHT, 1.0, std::mem::take(&mut self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{meter::Meter, observe::Observed};
+    use metrics::{Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName};
+    use metrics::{SharedString, Unit};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    static LOG: Mutex<Vec<(String, f64)>> = Mutex::new(Vec::new());
+
+    /// Logs every update as the metric name, followed by its host and status class labels.
+    struct Handle(String);
+
+    impl Handle {
+        fn log(&self, value: f64) {
+            LOG.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((self.0.clone(), value));
+        }
+    }
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            self.log(f64::from(u32::try_from(value).unwrap_or(u32::MAX)));
+        }
+        fn absolute(&self, _: u64) {}
+    }
+
+    impl GaugeFn for Handle {
+        fn increment(&self, value: f64) {
+            self.log(value);
+        }
+        fn decrement(&self, value: f64) {
+            self.log(-value);
+        }
+        fn set(&self, _: f64) {}
+    }
+
+    impl HistogramFn for Handle {
+        fn record(&self, value: f64) {
+            self.log(value);
+        }
+    }
+
+    struct Recorder;
+
+    impl Recorder {
+        fn handle(key: &Key) -> Arc<Handle> {
+            let labels = key
+                .labels()
+                .filter(|label| matches!(label.key(), "host" | "status_class"))
+                .map(|label| label.value().to_owned())
+                .collect::<Vec<_>>();
+            Arc::new(Handle(format!("{} {}", key.name(), labels.join(" "))))
+        }
+    }
+
+    impl metrics::Recorder for Recorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, key: &Key) -> Counter {
+            Counter::from_arc(Self::handle(key))
+        }
+        fn register_gauge(&self, key: &Key) -> Gauge {
+            Gauge::from_arc(Self::handle(key))
+        }
+        fn register_histogram(&self, key: &Key) -> Histogram {
+            Histogram::from_arc(Self::handle(key))
+        }
+    }
+
+    struct Response;
+
+    impl Observed for Response {
+        #[cfg(feature = "tracing")]
+        fn protocol_version(&self) -> Option<&'static str> {
+            None
+        }
+        fn status(&self) -> u16 {
+            404
+        }
+        fn body_size(&self) -> usize {
+            3
+        }
+        fn redirect_count(&self) -> usize {
+            1
+        }
+    }
+
+    #[tokio::test]
+    async fn request_metrics() {
+        let _ = metrics::set_boxed_recorder(Box::new(Recorder));
+
+        let result = Meter::new("hyper", "GET", "https://metrics.example.invalid/users", 5)
+            .measure(async { Ok::<_, ()>(Response) })
+            .await;
+        assert!(result.is_ok());
+
+        let log = LOG.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let total = |name: &str| {
+            log.iter()
+                .filter(|(logged, _)| logged == name)
+                .map(|(_, value)| value)
+                .sum::<f64>()
+        };
+        assert!(
+            (total("http_client_requests_total metrics.example.invalid 4xx") - 1.0).abs()
+                < f64::EPSILON
+        );
+        assert!(
+            total("http_client_requests_in_flight metrics.example.invalid").abs() < f64::EPSILON
+        );
+        assert!(
+            (total("http_client_redirects_total metrics.example.invalid") - 1.0).abs()
+                < f64::EPSILON
+        );
+        assert!(
+            (total("http_client_request_body_bytes_total metrics.example.invalid") - 5.0).abs()
+                < f64::EPSILON
+        );
+        assert!(
+            (total("http_client_response_body_bytes_total metrics.example.invalid") - 3.0).abs()
+                < f64::EPSILON
+        );
+        assert!(log
+            .iter()
+            .any(|(name, _)| name
+                == "http_client_request_duration_seconds metrics.example.invalid 4xx"));
+    }
+}
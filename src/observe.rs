@@ -0,0 +1,68 @@
+//! What the `tracing` spans and `metrics` of a request record about it, and the clock and
+//! host keys of the circuit breaker.
+
+/// The response of an engine, as seen by its span and metrics.
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+pub trait Observed {
+    fn status(&self) -> u16;
+    /// `network.protocol.version`, such as `1.1` or `2`.
+    #[cfg(feature = "tracing")]
+    fn protocol_version(&self) -> Option<&'static str>;
+    fn body_size(&self) -> usize;
+    /// Redirects followed before the response.
+    fn redirect_count(&self) -> usize;
+}
+
+#[cfg(all(
+    feature = "tracing",
+    any(feature = "__hyper", feature = "__reqwest")
+))]
+pub const fn protocol_version(version: hyper::Version) -> Option<&'static str> {
+    match version {
+        hyper::Version::HTTP_09 => Some("0.9"),
+        hyper::Version::HTTP_10 => Some("1.0"),
+        hyper::Version::HTTP_11 => Some("1.1"),
+        hyper::Version::HTTP_2 => Some("2"),
+        hyper::Version::HTTP_3 => Some("3"),
+        _ => None,
+    }
+}
+
+/// Milliseconds from an arbitrary point, `Instant` is not available in browsers.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now() -> f64 {
+    static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    START
+        .get_or_init(std::time::Instant::now)
+        .elapsed()
+        .as_secs_f64()
+        * 1000.0
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+/// `server.address` and `server.port`, the port falls back to the default of the scheme.
+pub fn server(url: &str) -> (&str, Option<u16>) {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return ("", None);
+    };
+    let authority = rest.split(['/', '?']).next().unwrap_or(rest);
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+
+    // the port follows the closing bracket of IPv6 addresses
+    let (address, port) = match host.rfind(':') {
+        Some(colon) if !host[colon..].contains(']') => {
+            (&host[..colon], host[colon + 1..].parse().ok())
+        }
+        _ => (host, None),
+    };
+    let port = port.or(match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    });
+    (address.trim_start_matches('[').trim_end_matches(']'), port)
+}
@@ -0,0 +1,348 @@
+use futures_util::Stream;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Formatter},
+    future::Future,
+    sync::Arc,
+};
+
+/// A page as returned by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub body: Vec<u8>,
+    /// The `Link` header, when the engine can read it.
+    pub link: Option<String>,
+}
+
+/// An engine that fetches pages.
+pub trait Get {
+    type Error;
+
+    /// The URL the engine requests for `path`.
+    fn url(&self, path: &str) -> String;
+
+    /// GETs the absolute `url`, other statuses than `200 OK` are errors.
+    fn get(&self, url: &str) -> impl Future<Output = Result<Response, Self::Error>>;
+}
+
+/// A fetched page, as seen by [`Next`].
+#[derive(Debug)]
+pub struct Page<'a> {
+    /// The URL the page was requested from.
+    pub url: &'a str,
+    pub link: Option<&'a str>,
+    pub body: &'a serde_json::Value,
+    /// The items found on the page.
+    pub items: usize,
+}
+
+/// Where the page after the current one is, pagination ends once there is none.
+#[derive(Clone)]
+pub enum Next {
+    /// The `rel="next"` target of the `Link` header, as of RFC 5988.
+    Link,
+    /// A cursor at the JSON pointer `field` of the body, sent as the query parameter `param`.
+    /// Ends on a missing, `null` or empty cursor.
+    Cursor { field: String, param: String },
+    /// Counts the items received so far in the query parameter `param`. Ends on a page with
+    /// fewer than `limit` items, which the first path is expected to ask for.
+    Offset { param: String, limit: usize },
+    /// The URL of the next page.
+    Custom(Extractor),
+}
+
+type Extractor = Arc<dyn Fn(&Page) -> Option<String> + Send + Sync>;
+
+impl Next {
+    #[must_use]
+    pub fn cursor(field: impl Into<String>, param: impl Into<String>) -> Self {
+        Self::Cursor {
+            field: field.into(),
+            param: param.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn offset(param: impl Into<String>, limit: usize) -> Self {
+        Self::Offset {
+            param: param.into(),
+            limit,
+        }
+    }
+
+    #[must_use]
+    pub fn custom(next: impl Fn(&Page) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(next))
+    }
+
+    /// The URL of the page after `page`.
+    #[must_use]
+    pub fn next(&self, page: &Page) -> Option<String> {
+        match self {
+            Self::Link => page
+                .link
+                .and_then(next_link)
+                .map(|link| resolve(page.url, link)),
+            Self::Cursor { field, param } => {
+                let cursor = match page.body.pointer(field)? {
+                    serde_json::Value::String(cursor) => cursor.clone(),
+                    serde_json::Value::Number(cursor) => cursor.to_string(),
+                    _ => return None,
+                };
+                (!cursor.is_empty()).then(|| with_query(page.url, param, &cursor))
+            }
+            Self::Offset { param, limit } => {
+                if page.items == 0 || page.items < *limit {
+                    return None;
+                }
+                let offset = query(page.url, param)
+                    .and_then(|offset| offset.parse::<usize>().ok())
+                    .unwrap_or(0);
+                Some(with_query(
+                    page.url,
+                    param,
+                    &(offset + page.items).to_string(),
+                ))
+            }
+            Self::Custom(next) => next(page),
+        }
+    }
+}
+
+impl Debug for Next {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Link => write!(f, "Link"),
+            Self::Cursor { field, param } => write!(f, "Cursor({field}, {param})"),
+            Self::Offset { param, limit } => write!(f, "Offset({param}, {limit})"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Requests page after page and returns their items, created by the `paginate` method of an
+/// engine.
+///
+/// Pages go through the layers of the engine. A failed page ends the pagination after its
+/// error is returned.
+pub struct Paginator<C: Get, T> {
+    client: C,
+    next: Option<String>,
+    buffered: VecDeque<T>,
+    pages: usize,
+    /// Finds the page after the current one.
+    pub strategy: Next,
+    /// The JSON pointer to the array of items in a page, the whole body when empty.
+    pub items: String,
+    /// Stops after this many pages.
+    pub max_pages: Option<usize>,
+}
+
+impl<C: Get, T: DeserializeOwned> Paginator<C, T>
+where
+    C::Error: From<serde_json::Error>,
+{
+    pub(crate) fn new(client: C, path: &str, strategy: Next) -> Self {
+        Self {
+            next: Some(client.url(path)),
+            client,
+            buffered: VecDeque::new(),
+            pages: 0,
+            strategy,
+            items: String::new(),
+            max_pages: None,
+        }
+    }
+
+    /// The items of the next page, `None` once there is no next page.
+    pub async fn next_page(&mut self) -> Option<Result<Vec<T>, C::Error>> {
+        if self.max_pages.is_some_and(|max| self.pages >= max) {
+            return None;
+        }
+        let url = self.next.take()?;
+        self.pages += 1;
+        Some(self.page(url).await)
+    }
+
+    /// The next item, across pages.
+    pub async fn next(&mut self) -> Option<Result<T, C::Error>> {
+        loop {
+            if let Some(item) = self.buffered.pop_front() {
+                return Some(Ok(item));
+            }
+            match self.next_page().await? {
+                Ok(items) => self.buffered.extend(items),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+
+    /// Every item across pages.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T, C::Error>> {
+        futures_util::stream::unfold(self, |mut paginator| async move {
+            Some((paginator.next().await?, paginator))
+        })
+    }
+
+    async fn page(&mut self, url: String) -> Result<Vec<T>, C::Error> {
+        let response = self.client.get(&url).await?;
+        let body: serde_json::Value = serde_json::from_slice(&response.body)?;
+        let items: Vec<T> = match body.pointer(&self.items) {
+            Some(items) => serde_json::from_value(items.clone())?,
+            None => Vec::new(),
+        };
+
+        self.next = self
+            .strategy
+            .next(&Page {
+                url: &url,
+                link: response.link.as_deref(),
+                body: &body,
+                items: items.len(),
+            })
+            // a page pointing to itself would never end
+            .filter(|next| *next != url);
+        Ok(items)
+    }
+}
+
+/// The target of the `rel="next"` link in a `Link` header.
+fn next_link(header: &str) -> Option<&str> {
+    let mut rest = header;
+    while let Some(start) = rest.find('<') {
+        let end = start + rest[start..].find('>')?;
+        let target = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        let params = &rest[..rest.find('<').unwrap_or(rest.len())];
+
+        let next = params.split(';').any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+            })
+        });
+        if next {
+            return Some(target);
+        }
+    }
+    None
+}
+
+/// `link` relative to `url`.
+fn resolve(url: &str, link: &str) -> String {
+    if link.contains("://") {
+        return link.to_owned();
+    }
+    let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+    let origin = &rest[..rest.find('/').unwrap_or(rest.len())];
+    if link.starts_with('/') {
+        return format!("{scheme}://{origin}{link}");
+    }
+    let path = &rest[origin.len()..];
+    let path = &path[..path.find('?').unwrap_or(path.len())];
+    let directory = &path[..path.rfind('/').map_or(0, |end| end + 1)];
+    let directory = if directory.is_empty() { "/" } else { directory };
+    if let Some(query) = link.strip_prefix('?') {
+        return format!("{scheme}://{origin}{path}?{query}");
+    }
+    format!("{scheme}://{origin}{directory}{link}")
+}
+
+/// The raw value of the query parameter `param` of `url`.
+fn query<'a>(url: &'a str, param: &str) -> Option<&'a str> {
+    url.split_once('?')?
+        .1
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(param)?.strip_prefix('='))
+}
+
+/// `url` with the query parameter `param` set to `value`.
+fn with_query(url: &str, param: &str, value: &str) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let pair = format!("{param}={}", encode(value));
+    let pairs = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(param))
+        .chain([pair.as_str()])
+        .collect::<Vec<_>>();
+    format!("{base}?{}", pairs.join("&"))
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::paginate::{next_link, Next, Page};
+
+    fn next(
+        strategy: &Next,
+        url: &str,
+        link: Option<&str>,
+        body: &str,
+        items: usize,
+    ) -> Option<String> {
+        let body = serde_json::from_str(body).unwrap_or_default();
+        strategy.next(&Page {
+            url,
+            link,
+            body: &body,
+            items,
+        })
+    }
+
+    #[test]
+    fn strategies() {
+        let url = "https://api.revolt.chat/servers/01/members?limit=2";
+        assert_eq!(
+            next_link(r#"<https://x/?page=1>; rel="prev first", </members?page=3>; rel="next""#),
+            Some("/members?page=3")
+        );
+        assert_eq!(
+            next(&Next::Link, url, Some("<?page=3>; rel=next"), "null", 2).as_deref(),
+            Some("https://api.revolt.chat/servers/01/members?page=3")
+        );
+        assert_eq!(
+            next(&Next::Link, url, Some("<a>; rel=last"), "null", 2),
+            None
+        );
+
+        let cursor = Next::cursor("/meta/after", "after");
+        assert_eq!(
+            next(&cursor, url, None, r#"{"meta":{"after":"01 H"}}"#, 2).as_deref(),
+            Some("https://api.revolt.chat/servers/01/members?limit=2&after=01%20H")
+        );
+        assert_eq!(
+            next(&cursor, url, None, r#"{"meta":{"after":null}}"#, 2),
+            None
+        );
+
+        let offset = Next::offset("offset", 2);
+        let second = next(&offset, url, None, "[]", 2);
+        assert_eq!(
+            second.as_deref(),
+            Some("https://api.revolt.chat/servers/01/members?limit=2&offset=2")
+        );
+        assert_eq!(
+            next(&offset, &second.unwrap_or_default(), None, "[]", 2).as_deref(),
+            Some("https://api.revolt.chat/servers/01/members?limit=2&offset=4")
+        );
+        // a short page is the last one
+        assert_eq!(next(&offset, url, None, "[]", 1), None);
+    }
+}
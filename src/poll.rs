@@ -0,0 +1,198 @@
+use crate::timer::sleep;
+use futures_util::Stream;
+use std::{
+    collections::hash_map::{DefaultHasher, RandomState},
+    future::Future,
+    hash::{BuildHasher, Hash, Hasher},
+    time::Duration,
+};
+
+/// A response to a poll that was not answered with `304 Not Modified`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched {
+    pub body: Vec<u8>,
+    /// Sent as `If-None-Match` by the next poll.
+    pub etag: Option<String>,
+}
+
+/// An engine that polls.
+pub trait Fetch {
+    type Error;
+
+    /// GETs `path` with `If-None-Match` when an `ETag` is given. `None` when the server answered
+    /// `304 Not Modified`, other statuses than `200 OK` are errors.
+    fn fetch(
+        &self,
+        path: &str,
+        etag: Option<&str>,
+    ) -> impl Future<Output = Result<Option<Fetched>, Self::Error>>;
+}
+
+/// Requests a path on an interval and returns its body only once it changed, created by the
+/// `poll` method of an engine.
+///
+/// Polls go through the layers of the engine. Nothing runs in the background, polling stops
+/// once the poller or its stream is dropped, for example by `StreamExt::take_until`.
+pub struct Poller<C: Fetch> {
+    client: C,
+    path: String,
+    etag: Option<String>,
+    /// The hash of the latest body.
+    last: Option<u64>,
+    polled: bool,
+    /// The delay between polls, zero for long polling where the server holds the request.
+    pub interval: Duration,
+    /// Up to this much is added to every delay at random, so clients do not poll in step.
+    pub jitter: Duration,
+}
+
+impl<C: Fetch> Poller<C> {
+    pub(crate) const fn new(client: C, path: String) -> Self {
+        Self {
+            client,
+            path,
+            etag: None,
+            last: None,
+            polled: false,
+            interval: Duration::from_secs(30),
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// The body once it differs from the previous one, the first poll always returns it.
+    ///
+    /// A failed poll is returned as an error, the next call polls again after the interval.
+    pub async fn next(&mut self) -> Result<Vec<u8>, C::Error> {
+        loop {
+            if self.polled {
+                sleep(self.interval + random(self.jitter)).await;
+            }
+            self.polled = true;
+
+            let Some(fetched) = self.client.fetch(&self.path, self.etag.as_deref()).await? else {
+                continue;
+            };
+            self.etag = fetched.etag;
+            let mut hasher = DefaultHasher::new();
+            fetched.body.hash(&mut hasher);
+            let hash = hasher.finish();
+            // servers without ETags send the same body again
+            if self.last.replace(hash) != Some(hash) {
+                return Ok(fetched.body);
+            }
+        }
+    }
+
+    /// The body once it changed, deserialized as JSON.
+    #[cfg(feature = "serde")]
+    pub async fn next_json<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, C::Error>
+    where
+        C::Error: From<serde_json::Error>,
+    {
+        Ok(serde_json::from_slice(&self.next().await?)?)
+    }
+
+    /// Every changed body, the stream never ends by itself.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<u8>, C::Error>> {
+        futures_util::stream::unfold(self, |mut poller| async move {
+            Some((poller.next().await, poller))
+        })
+    }
+
+    /// Every changed body deserialized as JSON, the stream never ends by itself.
+    #[cfg(feature = "serde")]
+    pub fn into_json_stream<T: serde::de::DeserializeOwned>(
+        self,
+    ) -> impl Stream<Item = Result<T, C::Error>>
+    where
+        C::Error: From<serde_json::Error>,
+    {
+        futures_util::stream::unfold(self, |mut poller| async move {
+            Some((poller.next_json().await, poller))
+        })
+    }
+}
+
+/// A random duration up to `max`.
+fn random(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    // every `RandomState` is seeded anew
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_nanos(random % nanos.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poll::{random, Fetch, Fetched, Poller};
+    use std::{
+        collections::VecDeque,
+        sync::{Mutex, PoisonError},
+        time::Duration,
+    };
+
+    /// Answers polls in order, recording the `ETag`s sent.
+    struct Responses {
+        responses: Mutex<VecDeque<Option<Fetched>>>,
+        sent: Mutex<Vec<Option<String>>>,
+    }
+
+    impl Fetch for Responses {
+        type Error = ();
+
+        async fn fetch(&self, _: &str, etag: Option<&str>) -> Result<Option<Fetched>, ()> {
+            self.sent
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(etag.map(str::to_owned));
+            self.responses
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .pop_front()
+                .ok_or(())
+        }
+    }
+
+    fn fetched(body: &str, etag: Option<&str>) -> Fetched {
+        Fetched {
+            body: body.into(),
+            etag: etag.map(str::to_owned),
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_only() {
+        let responses = Responses {
+            responses: Mutex::new(VecDeque::from([
+                Some(fetched("1", Some("a"))),
+                None,
+                Some(fetched("2", None)),
+                Some(fetched("2", None)),
+                Some(fetched("3", None)),
+            ])),
+            sent: Mutex::default(),
+        };
+        let mut poller = Poller::new(responses, String::new());
+        poller.interval = Duration::ZERO;
+
+        assert_eq!(poller.next().await, Ok(b"1".to_vec()));
+        // not modified and an unchanged body are skipped
+        assert_eq!(poller.next().await, Ok(b"2".to_vec()));
+        assert_eq!(poller.next().await, Ok(b"3".to_vec()));
+        assert_eq!(poller.next().await, Err(()));
+
+        let sent = poller
+            .client
+            .sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let a = Some(String::from("a"));
+        assert_eq!(sent, [None, a.clone(), a, None, None, None]);
+
+        assert!(random(Duration::from_millis(5)) <= Duration::from_millis(5));
+        assert_eq!(random(Duration::ZERO), Duration::ZERO);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::Arc,
+};
+
+/// How an engine reacts to a `3xx` response with a `Location` header.
+///
+/// The same policy behaves the same way on the native engines, the default follows up to 10
+/// redirects.
+///
+/// The browser follows redirects itself for `Reqwasm`: `Limited` and `Custom` follow every
+/// redirect up to the limit of the browser, and under `None` the browser hides the redirect
+/// response, so requests fail with `Error::OpaqueRedirect` instead of returning it.
+#[derive(Clone)]
+pub enum Policy {
+    /// Redirect responses are returned as is, an error on `Reqwasm`.
+    None,
+    /// Follows at most this many redirects, any further redirect is an error. `Reqwasm`
+    /// ignores the limit.
+    Limited(usize),
+    /// Decides per redirect, not called on `Reqwasm`.
+    Custom(Arc<dyn Fn(&Attempt) -> Action + Send + Sync>),
+}
+
+/// A redirect the engine is about to follow.
+#[derive(Debug)]
+pub struct Attempt<'a> {
+    pub status: u16,
+    /// Where the redirect points to.
+    pub next: &'a str,
+    /// Every URL requested so far, the last being the one that returned this redirect.
+    pub previous: &'a [String],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Follow,
+    /// Returns the redirect response to the caller.
+    Stop,
+    /// Fails the request with [`TooManyRedirects`].
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyRedirects;
+
+impl Policy {
+    #[must_use]
+    pub fn custom(policy: impl Fn(&Attempt) -> Action + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(policy))
+    }
+
+    #[must_use]
+    pub fn action(&self, attempt: &Attempt) -> Action {
+        match self {
+            Self::None => Action::Stop,
+            Self::Limited(max) if attempt.previous.len() > *max => Action::Error,
+            Self::Limited(_) => Action::Follow,
+            Self::Custom(policy) => policy(attempt),
+        }
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::Limited(10)
+    }
+}
+
+impl Debug for Policy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Limited(max) => write!(f, "Limited({max})"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl Display for TooManyRedirects {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "too many redirects")
+    }
+}
+
+impl std::error::Error for TooManyRedirects {}
+
+/// Whether a `GET` without a body replaces the original request, as browsers do for 301 and 302
+/// after a `POST` and for every 303 except a `HEAD`.
+#[cfg(feature = "__hyper")]
+pub(crate) fn switches_to_get(status: u16, method: &str) -> bool {
+    match status {
+        301 | 302 => method == "POST",
+        303 => method != "GET" && method != "HEAD",
+        _ => false,
+    }
+}
+
+/// Credentials are only sent back to the origin they were set for.
+#[cfg(feature = "__hyper")]
+pub(crate) fn is_cross_origin(from: &url::Url, to: &url::Url) -> bool {
+    from.origin() != to.origin()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redirect::{Action, Attempt, Policy};
+
+    #[test]
+    fn limited_policy() {
+        let previous = vec![String::from("https://a.example"); 3];
+        let attempt = |count| Attempt {
+            status: 302,
+            next: "https://b.example",
+            previous: &previous[..count],
+        };
+
+        assert_eq!(Policy::None.action(&attempt(1)), Action::Stop);
+        assert_eq!(Policy::Limited(2).action(&attempt(2)), Action::Follow);
+        assert_eq!(Policy::Limited(2).action(&attempt(3)), Action::Error);
+        assert_eq!(
+            Policy::custom(|attempt| if attempt.status == 302 {
+                Action::Stop
+            } else {
+                Action::Follow
+            })
+            .action(&attempt(1)),
+            Action::Stop
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use hyper::HeaderMap;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+};
+use tokio::sync::watch;
+
+type Calls<T> = Arc<Mutex<HashMap<String, watch::Receiver<Option<Option<T>>>>>>;
+
+/// Coalesces concurrent identical `GET` requests into one, every caller receives a clone of
+/// its response. Clones share the requests in flight.
+///
+/// Errors can not be cloned, when the shared request fails the waiting callers send their own.
+pub struct SingleFlight<T> {
+    calls: Calls<T>,
+}
+
+impl<T: Clone + Send + Sync> SingleFlight<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `request`, or waits for the one in flight under `key` instead.
+    pub(crate) async fn run<E: Send>(
+        &self,
+        key: String,
+        request: impl Future<Output = Result<T, E>> + Send,
+    ) -> Result<T, E> {
+        // a receiver to wait on, or the sender of the new leader
+        let role = {
+            let mut calls = self.calls.lock().unwrap_or_else(PoisonError::into_inner);
+            calls.get(&key).cloned().map_or_else(
+                || {
+                    let (sender, receiver) = watch::channel(None);
+                    calls.insert(key.clone(), receiver);
+                    Err(sender)
+                },
+                Ok,
+            )
+        };
+        let mut receiver = match role {
+            Ok(receiver) => receiver,
+            Err(sender) => {
+                // also removed when the caller stops waiting, its followers then send their own
+                let leader = Leader {
+                    calls: self.calls.clone(),
+                    key,
+                };
+                let result = request.await;
+                drop(leader);
+                let _ = sender.send(Some(result.as_ref().ok().cloned()));
+                return result;
+            }
+        };
+
+        if let Ok(response) = receiver.wait_for(Option::is_some).await {
+            if let Some(Some(response)) = &*response {
+                return Ok(response.clone());
+            }
+        }
+        request.await
+    }
+}
+
+/// `METHOD URL` followed by the sorted headers, requests with other headers may vary.
+pub(crate) fn key(method: &str, url: &str, headers: &HeaderMap) -> String {
+    let mut headers = headers
+        .iter()
+        .map(|(key, value)| format!("{key}: {}", String::from_utf8_lossy(value.as_bytes())))
+        .collect::<Vec<_>>();
+    headers.sort();
+    format!("{method} {url}\n{}", headers.join("\n"))
+}
+
+struct Leader<T> {
+    calls: Calls<T>,
+    key: String,
+}
+
+impl<T> Drop for Leader<T> {
+    fn drop(&mut self) {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.key);
+    }
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self {
+            calls: Arc::default(),
+        }
+    }
+}
+
+impl<T> Clone for SingleFlight<T> {
+    fn clone(&self) -> Self {
+        Self {
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+impl<T> Debug for SingleFlight<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleFlight").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,262 @@
+use crate::timer::sleep;
+use std::{collections::VecDeque, future::Future, time::Duration};
+
+/// A message of a `text/event-stream`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The `event` field, `message` when the server did not name it.
+    pub event: String,
+    /// Every `data` field of the event, joined by newlines.
+    pub data: String,
+    /// The latest `id` sent by the server, also for events without their own.
+    pub id: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl Event {
+    /// Deserializes `data` as JSON.
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.data)
+    }
+}
+
+/// An engine that opens event streams.
+pub trait Connect {
+    /// The body of an open stream.
+    type Response;
+    type Error;
+
+    /// Opens the stream at `path` with `Last-Event-ID` when an ID is given. `None` when the
+    /// server answered `204 No Content`, other statuses than `200 OK` are errors.
+    fn connect(
+        &self,
+        path: &str,
+        last_event_id: Option<&str>,
+    ) -> impl Future<Output = Result<Option<Self::Response>, Self::Error>>;
+
+    /// The next bytes of the stream, `None` once the connection ended or failed.
+    fn chunk(response: &mut Self::Response) -> impl Future<Output = Option<Vec<u8>>>;
+}
+
+/// Receives the events of a server, reconnecting with `Last-Event-ID` whenever the connection
+/// ends. Created by the `event_source` method of an engine.
+///
+/// Event streams bypass caches, cassettes and the layers around `common`.
+pub struct EventSource<C: Connect> {
+    client: C,
+    path: String,
+    parser: Parser,
+    events: VecDeque<Event>,
+    response: Option<C::Response>,
+    /// Whether a connection was opened before, the next waits for `retry` first.
+    reconnect: bool,
+    closed: bool,
+    /// The delay before reconnecting, until the server sets its own.
+    pub retry: Duration,
+}
+
+impl<C: Connect> EventSource<C> {
+    pub(crate) fn new(client: C, path: String) -> Self {
+        Self {
+            client,
+            path,
+            parser: Parser::default(),
+            events: VecDeque::new(),
+            response: None,
+            reconnect: false,
+            closed: false,
+            retry: Duration::from_secs(3),
+        }
+    }
+
+    /// Sent as `Last-Event-ID` when reconnecting.
+    #[must_use]
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.id.as_deref()
+    }
+
+    /// The next event, `None` once the server answered `204 No Content`.
+    ///
+    /// A failed connection attempt is returned as an error, the next call tries again after
+    /// `retry`.
+    pub async fn next(&mut self) -> Option<Result<Event, C::Error>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.closed {
+                return None;
+            }
+
+            let Some(response) = &mut self.response else {
+                if self.reconnect {
+                    sleep(self.retry).await;
+                }
+                self.reconnect = true;
+                match self.client.connect(&self.path, self.last_event_id()).await {
+                    Ok(Some(response)) => self.response = Some(response),
+                    Ok(None) => self.closed = true,
+                    Err(error) => return Some(Err(error)),
+                }
+                continue;
+            };
+
+            if let Some(chunk) = C::chunk(response).await {
+                self.events.extend(self.parser.feed(&chunk));
+                if let Some(retry) = self.parser.retry.take() {
+                    self.retry = retry;
+                }
+            } else {
+                // a partial event is discarded with its connection
+                self.response = None;
+                self.parser.reset();
+            }
+        }
+    }
+
+    /// The data of the next event, deserialized as JSON.
+    #[cfg(feature = "serde")]
+    pub async fn next_json<T: serde::de::DeserializeOwned>(&mut self) -> Option<Result<T, C::Error>>
+    where
+        C::Error: From<serde_json::Error>,
+    {
+        Some(self.next().await?.and_then(|event| Ok(event.json()?)))
+    }
+}
+
+/// Splits a stream into events, following the WHATWG `text/event-stream` parsing rules.
+#[derive(Debug, Default)]
+pub(crate) struct Parser {
+    buffer: Vec<u8>,
+    started: bool,
+    event: Option<String>,
+    data: Option<String>,
+    /// The ID of the last dispatched event, sent as `Last-Event-ID` when reconnecting.
+    id: Option<String>,
+    /// The latest `id` field, it becomes the last event ID once its event is dispatched.
+    pending_id: Option<String>,
+    /// The latest `retry` field, taken by the event source.
+    retry: Option<Duration>,
+}
+
+impl Parser {
+    /// Events completed by `chunk`.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        self.buffer.extend_from_slice(chunk);
+        if !self.started && self.buffer.len() >= 3 {
+            self.started = true;
+            if self.buffer.starts_with(b"\xEF\xBB\xBF") {
+                self.buffer.drain(..3);
+            }
+        }
+
+        let mut events = Vec::new();
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..]
+            .iter()
+            .position(|byte| matches!(byte, b'\r' | b'\n'))
+            .map(|end| start + end)
+        {
+            // a carriage return at the end may be followed by a line feed in the next chunk
+            let next = match self.buffer.get(end + 1) {
+                Some(b'\n') if self.buffer[end] == b'\r' => end + 2,
+                None if self.buffer[end] == b'\r' => break,
+                _ => end + 1,
+            };
+            let line = String::from_utf8_lossy(&self.buffer[start..end]).into_owned();
+            events.extend(self.line(&line));
+            start = next;
+        }
+        self.buffer.drain(..start);
+        events
+    }
+
+    /// Drops the incomplete event along with its ID, the last event ID is kept.
+    pub(crate) fn reset(&mut self) {
+        self.buffer.clear();
+        self.started = false;
+        self.event = None;
+        self.data = None;
+        self.pending_id = self.id.clone();
+    }
+
+    fn line(&mut self, line: &str) -> Option<Event> {
+        if line.is_empty() {
+            self.id = self.pending_id.clone();
+            let event = self.event.take();
+            return self.data.take().map(|data| Event {
+                event: event.unwrap_or_else(|| String::from("message")),
+                data,
+                id: self.id.clone(),
+            });
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_owned()),
+            "data" => match &mut self.data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => self.data = Some(value.to_owned()),
+            },
+            "id" if !value.contains('\0') => {
+                self.pending_id = Some(value.to_owned()).filter(|id| !id.is_empty());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) => {
+                self.retry = value.parse().ok().map(Duration::from_millis);
+            }
+            // comments start with a colon, unknown fields are ignored
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sse::Parser;
+    use std::time::Duration;
+
+    #[test]
+    fn event_stream_parsing() {
+        let mut parser = Parser::default();
+        assert!(parser
+            .feed(b"\xEF\xBB\xBF: comment\r\nevent: update\r\ndata: {\"a\":")
+            .is_empty());
+
+        let events = parser.feed(b"1}\r\ndata:2\r\nid: 7\r\nretry: 500\r\n\r\ndata\n\nid\nfoo\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "update");
+        assert_eq!(events[0].data, "{\"a\":1}\n2");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+        assert_eq!(parser.retry, Some(Duration::from_millis(500)));
+        // the ID carries over, an empty `data` field is an empty event
+        assert_eq!(
+            (events[1].event.as_str(), events[1].data.as_str()),
+            ("message", "")
+        );
+        assert_eq!(events[1].id.as_deref(), Some("7"));
+        // `id` without a value resets it, events without data are not sent
+        assert_eq!(parser.id, None);
+
+        // a carriage return split from its line feed ends a single line
+        assert!(parser.feed(b"data: x\r").is_empty());
+        assert_eq!(parser.feed(b"\n\r\n")[0].data, "x");
+    }
+
+    #[test]
+    fn connection_cut_mid_event() {
+        let mut parser = Parser::default();
+        assert_eq!(parser.feed(b"id: 1\ndata: a\n\nid: 2\ndata: b").len(), 1);
+        // the second event is sent again after reconnecting, from the first one
+        parser.reset();
+        assert_eq!(parser.id.as_deref(), Some("1"));
+
+        let events = parser.feed(b"data: b\n\n");
+        assert_eq!(events[0].data, "b");
+        assert_eq!(events[0].id.as_deref(), Some("1"));
+    }
+}
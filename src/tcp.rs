@@ -0,0 +1,108 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+/// Socket options of the native engines, unset options keep the defaults of the engine.
+#[derive(Debug, Clone, Default)]
+pub struct Tcp {
+    /// Outbound connections are bound to this address, takes precedence over `interface`.
+    pub local_address: Option<IpAddr>,
+    /// Outbound connections are bound to the addresses of this interface, such as `eth1`.
+    pub interface: Option<String>,
+    pub nodelay: Option<bool>,
+    pub keepalive: Option<Duration>,
+    /// `SO_SNDBUF`, hyper only.
+    pub send_buffer_size: Option<usize>,
+    /// `SO_RCVBUF`, hyper only.
+    pub recv_buffer_size: Option<usize>,
+}
+
+impl Tcp {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_local_address(&mut self, address: IpAddr) -> Self {
+        self.local_address = Some(address);
+        self.to_owned()
+    }
+    pub fn set_interface(&mut self, interface: impl Into<String>) -> Self {
+        self.interface = Some(interface.into());
+        self.to_owned()
+    }
+    pub fn set_nodelay(&mut self, nodelay: bool) -> Self {
+        self.nodelay = Some(nodelay);
+        self.to_owned()
+    }
+    pub fn set_keepalive(&mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval);
+        self.to_owned()
+    }
+    pub fn set_send_buffer_size(&mut self, size: usize) -> Self {
+        self.send_buffer_size = Some(size);
+        self.to_owned()
+    }
+    pub fn set_recv_buffer_size(&mut self, size: usize) -> Self {
+        self.recv_buffer_size = Some(size);
+        self.to_owned()
+    }
+
+    /// The local address of each family connections are bound to.
+    pub(crate) fn local_addresses(&self) -> io::Result<(Option<Ipv4Addr>, Option<Ipv6Addr>)> {
+        let addresses = match (self.local_address, &self.interface) {
+            (Some(address), _) => vec![address],
+            (None, Some(interface)) => if_addrs::get_if_addrs()?
+                .into_iter()
+                // link-local addresses can only reach the local network
+                .filter(|address| address.name == *interface && !address.is_link_local())
+                .map(|address| address.ip())
+                .collect(),
+            (None, None) => return Ok((None, None)),
+        };
+        if addresses.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no usable address on {}",
+                    self.interface.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+
+        Ok((
+            addresses.iter().find_map(|address| match address {
+                IpAddr::V4(address) => Some(*address),
+                IpAddr::V6(_) => None,
+            }),
+            addresses.iter().find_map(|address| match address {
+                IpAddr::V6(address) => Some(*address),
+                IpAddr::V4(_) => None,
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tcp::Tcp;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn local_addresses() -> std::io::Result<()> {
+        assert_eq!(Tcp::new().local_addresses()?, (None, None));
+        assert_eq!(
+            Tcp::new()
+                .set_interface("missing0")
+                .set_local_address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+                .local_addresses()?,
+            (Some(Ipv4Addr::LOCALHOST), None)
+        );
+        assert!(Tcp::new()
+            .set_interface("reywen-missing0")
+            .local_addresses()
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! Delays that also work in browsers, where tokio has no timer.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep(duration: Duration) {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let timeout = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|timeout| timeout.dyn_into::<js_sys::Function>().ok());
+        let _ = match timeout {
+            Some(timeout) => timeout.call2(
+                &JsValue::NULL,
+                &resolve,
+                &JsValue::from_f64(duration.as_secs_f64() * 1000.0),
+            ),
+            None => resolve.call0(&JsValue::NULL),
+        };
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
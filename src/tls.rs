@@ -0,0 +1,413 @@
+/// The lowest TLS version accepted, rustls never goes below TLS 1.2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    Tls10,
+    Tls11,
+    Tls12,
+    /// Not available with native-tls.
+    Tls13,
+}
+
+/// Client certificate presented for mutual TLS.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    /// PEM encoded certificate chain, starting with the client certificate.
+    pub certificate_chain: Vec<u8>,
+    /// PEM encoded private key, native-tls only reads PKCS #8 keys.
+    pub private_key: Vec<u8>,
+}
+
+/// TLS settings of the native engines, the default trusts the system roots only.
+///
+/// Which settings are available depends on the backend selected through the features,
+/// unsupported settings fail the request instead of being ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Tls {
+    /// PEM encoded certificates trusted on top of the system roots.
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Trusts `root_certificates` only.
+    pub no_system_roots: bool,
+    pub identity: Option<Identity>,
+    pub min_version: Option<Version>,
+    /// SHA-256 digests of a DER encoded `subjectPublicKeyInfo`, when any are set one of the
+    /// certificates sent by the server must match. rustls only.
+    pub pins: Vec<[u8; 32]>,
+    /// Accepts any server certificate, pins are still checked. Only meant for local testing.
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl Tls {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// `pem` may contain several certificates.
+    pub fn add_root_certificate(&mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self.to_owned()
+    }
+    pub fn set_system_roots(&mut self, enabled: bool) -> Self {
+        self.no_system_roots = !enabled;
+        self.to_owned()
+    }
+    pub fn set_identity(
+        &mut self,
+        certificate_chain: impl Into<Vec<u8>>,
+        private_key: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.identity = Some(Identity {
+            certificate_chain: certificate_chain.into(),
+            private_key: private_key.into(),
+        });
+        self.to_owned()
+    }
+    pub fn set_min_version(&mut self, version: Version) -> Self {
+        self.min_version = Some(version);
+        self.to_owned()
+    }
+    pub fn add_pin(&mut self, spki_sha256: [u8; 32]) -> Self {
+        self.pins.push(spki_sha256);
+        self.to_owned()
+    }
+    pub fn danger_accept_invalid_certs(&mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self.to_owned()
+    }
+
+    #[cfg(any(
+        all(feature = "hyper-native-tls", not(feature = "hyper-rustls")),
+        all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls"))
+    ))]
+    pub(crate) fn native_tls_unsupported(&self) -> std::io::Result<()> {
+        if !self.pins.is_empty() {
+            return Err(unsupported("certificate pinning requires rustls"));
+        }
+        if self.min_version == Some(Version::Tls13) {
+            return Err(unsupported("native-tls can not require TLS 1.3"));
+        }
+        Ok(())
+    }
+
+    #[cfg(all(feature = "hyper-native-tls", not(feature = "hyper-rustls")))]
+    pub(crate) fn native_tls(&self) -> std::io::Result<native_tls::TlsConnectorBuilder> {
+        self.native_tls_unsupported()?;
+
+        let mut builder = native_tls::TlsConnector::builder();
+        builder
+            .disable_built_in_roots(self.no_system_roots)
+            .danger_accept_invalid_certs(self.danger_accept_invalid_certs);
+
+        builder.min_protocol_version(self.min_version.map(|version| match version {
+            Version::Tls10 => native_tls::Protocol::Tlsv10,
+            Version::Tls11 => native_tls::Protocol::Tlsv11,
+            // TLS 1.3 is refused above
+            Version::Tls12 | Version::Tls13 => native_tls::Protocol::Tlsv12,
+        }));
+        for pem in self
+            .root_certificates
+            .iter()
+            .flat_map(|pem| pem_certificates(pem))
+        {
+            builder.add_root_certificate(
+                native_tls::Certificate::from_pem(pem).map_err(std::io::Error::other)?,
+            );
+        }
+        if let Some(identity) = &self.identity {
+            builder.identity(
+                native_tls::Identity::from_pkcs8(
+                    &identity.certificate_chain,
+                    &identity.private_key,
+                )
+                .map_err(std::io::Error::other)?,
+            );
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(any(
+    all(feature = "hyper-native-tls", not(feature = "hyper-rustls")),
+    all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls"))
+))]
+fn unsupported(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, message)
+}
+
+/// Splits PEM encoded certificates, native-tls reads one at a time.
+#[cfg(any(
+    all(feature = "hyper-native-tls", not(feature = "hyper-rustls")),
+    all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls"))
+))]
+pub(crate) fn pem_certificates(pem: &[u8]) -> impl Iterator<Item = &[u8]> {
+    const BEGIN: &[u8] = b"-----BEGIN CERTIFICATE-----";
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let mut rest = pem;
+    std::iter::from_fn(move || {
+        let start = find(rest, BEGIN)?;
+        let end = start + find(&rest[start..], END)? + END.len();
+        let certificate = &rest[start..end];
+        rest = &rest[end..];
+        Some(certificate)
+    })
+}
+
+#[cfg(feature = "__rustls")]
+mod rustls_config {
+    use crate::tls::{Tls, Version};
+    use rustls::{
+        client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+        Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName,
+    };
+    use sha2::{Digest, Sha256};
+    use std::{
+        io::{Error, ErrorKind},
+        sync::{Arc, OnceLock},
+        time::SystemTime,
+    };
+
+    impl Tls {
+        /// A rustls configuration without ALPN protocols, those depend on the engine.
+        pub fn rustls_config(&self) -> std::io::Result<ClientConfig> {
+            let mut roots = RootCertStore::empty();
+            if !self.no_system_roots {
+                for certificate in system_roots() {
+                    // unusable system certificates are skipped like browsers do
+                    let _ = roots.add(certificate);
+                }
+            }
+            for pem in &self.root_certificates {
+                for der in rustls_pemfile::certs(&mut pem.as_slice())? {
+                    roots.add(&Certificate(der)).map_err(invalid)?;
+                }
+            }
+
+            let versions: &[&rustls::SupportedProtocolVersion] = match self.min_version {
+                Some(Version::Tls13) => &[&rustls::version::TLS13],
+                _ => rustls::DEFAULT_VERSIONS,
+            };
+            let builder = ClientConfig::builder()
+                .with_safe_default_cipher_suites()
+                .with_safe_default_kx_groups()
+                .with_protocol_versions(versions)
+                .map_err(invalid)?;
+
+            let builder = builder.with_custom_certificate_verifier(Arc::new(Verifier {
+                webpki: (!self.danger_accept_invalid_certs)
+                    .then(|| WebPkiVerifier::new(roots, None)),
+                pins: self.pins.clone(),
+            }));
+
+            match &self.identity {
+                Some(identity) => {
+                    let chain = rustls_pemfile::certs(&mut identity.certificate_chain.as_slice())?
+                        .into_iter()
+                        .map(Certificate)
+                        .collect();
+                    let key = rustls_pemfile::read_all(&mut identity.private_key.as_slice())?
+                        .into_iter()
+                        .find_map(|item| match item {
+                            rustls_pemfile::Item::PKCS8Key(key)
+                            | rustls_pemfile::Item::RSAKey(key)
+                            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+                            _ => None,
+                        })
+                        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "no private key"))?;
+                    builder.with_client_auth_cert(chain, key).map_err(invalid)
+                }
+                None => Ok(builder.with_no_client_auth()),
+            }
+        }
+    }
+
+    /// Reading the system store is slow, it is only done once per process.
+    fn system_roots() -> &'static [Certificate] {
+        static ROOTS: OnceLock<Vec<Certificate>> = OnceLock::new();
+        ROOTS.get_or_init(|| {
+            rustls_native_certs::load_native_certs()
+                .map(|certificates| {
+                    certificates
+                        .into_iter()
+                        .map(|certificate| Certificate(certificate.0))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn invalid(error: rustls::Error) -> Error {
+        Error::new(ErrorKind::InvalidInput, error)
+    }
+
+    struct Verifier {
+        /// `None` accepts any certificate.
+        webpki: Option<WebPkiVerifier>,
+        pins: Vec<[u8; 32]>,
+    }
+
+    impl ServerCertVerifier for Verifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let verified = match &self.webpki {
+                Some(webpki) => webpki.verify_server_cert(
+                    end_entity,
+                    intermediates,
+                    server_name,
+                    scts,
+                    ocsp_response,
+                    now,
+                )?,
+                None => ServerCertVerified::assertion(),
+            };
+
+            let pinned = std::iter::once(end_entity)
+                .chain(intermediates)
+                .filter_map(|certificate| spki(&certificate.0))
+                .any(|spki| self.pins.contains(&Sha256::digest(spki).into()));
+
+            if self.pins.is_empty() || pinned {
+                Ok(verified)
+            } else {
+                Err(rustls::Error::General(String::from(
+                    "no certificate matches the pinned keys",
+                )))
+            }
+        }
+    }
+
+    /// The DER encoded `subjectPublicKeyInfo` of a certificate, the seventh field of
+    /// `tbsCertificate` when counting the optional version.
+    pub(super) fn spki(certificate: &[u8]) -> Option<&[u8]> {
+        let (certificate, _) = der(certificate)?;
+        let (tbs_certificate, _) = der(certificate.content)?;
+
+        let mut rest = tbs_certificate.content;
+        if rest.first() == Some(&0xa0) {
+            rest = der(rest)?.1;
+        }
+        // serialNumber, signature, issuer, validity, subject
+        for _ in 0..5 {
+            rest = der(rest)?.1;
+        }
+        Some(der(rest)?.0.element)
+    }
+
+    struct Der<'a> {
+        element: &'a [u8],
+        content: &'a [u8],
+    }
+
+    /// Splits the first element of `input` from the rest.
+    fn der(input: &[u8]) -> Option<(Der<'_>, &[u8])> {
+        let length = *input.get(1)?;
+        let (header, length) = if length < 0x80 {
+            (2, usize::from(length))
+        } else {
+            let bytes = usize::from(length & 0x7f);
+            let length = input
+                .get(2..2 + bytes)
+                .filter(|_| bytes <= std::mem::size_of::<usize>())?
+                .iter()
+                .fold(0, |length, byte| length << 8 | usize::from(*byte));
+            (2 + bytes, length)
+        };
+        let end = header
+            .checked_add(length)
+            .filter(|end| *end <= input.len())?;
+
+        Some((
+            Der {
+                element: &input[..end],
+                content: &input[header..end],
+            },
+            &input[end..],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "__rustls")]
+    #[test]
+    fn spki_of_certificate() {
+        fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+            let mut element = vec![tag];
+            let [high, low] = u16::try_from(content.len())
+                .unwrap_or(u16::MAX)
+                .to_be_bytes();
+            if high == 0 && low < 0x80 {
+                element.push(low);
+            } else {
+                element.extend([0x82, high, low]);
+            }
+            element.extend(content);
+            element
+        }
+
+        let spki = der(0x30, &[0x55; 200]);
+        let tbs_certificate = [
+            der(0xa0, &der(0x02, &[2])),
+            der(0x02, &[1]),
+            der(0x30, &[]),
+            der(0x30, &[]),
+            der(0x30, &[]),
+            der(0x30, &[]),
+            spki.clone(),
+        ]
+        .concat();
+        let certificate = der(
+            0x30,
+            &[der(0x30, &tbs_certificate), der(0x30, &[])].concat(),
+        );
+
+        assert_eq!(
+            crate::tls::rustls_config::spki(&certificate),
+            Some(spki.as_slice())
+        );
+        assert_eq!(crate::tls::rustls_config::spki(&certificate[..40]), None);
+    }
+
+    #[cfg(any(
+        all(feature = "hyper-native-tls", not(feature = "hyper-rustls")),
+        all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls"))
+    ))]
+    #[test]
+    fn pem_certificates_split() {
+        let pem = b"-----BEGIN CERTIFICATE-----\nAA==\n-----END CERTIFICATE-----\n\
+            -----BEGIN CERTIFICATE-----\nAQ==\n-----END CERTIFICATE-----\n";
+        assert_eq!(crate::tls::pem_certificates(pem).count(), 2);
+    }
+
+    #[cfg(any(
+        all(feature = "hyper-native-tls", not(feature = "hyper-rustls")),
+        all(feature = "reqwest-native-tls", not(feature = "reqwest-rustls"))
+    ))]
+    #[test]
+    fn native_tls_min_version() {
+        use crate::tls::{Tls, Version};
+
+        // versions below TLS 1.2 are kept, TLS 1.3 can not be required
+        for version in [Version::Tls10, Version::Tls11, Version::Tls12] {
+            assert!(Tls::new()
+                .set_min_version(version)
+                .native_tls_unsupported()
+                .is_ok());
+        }
+        assert!(Tls::new()
+            .set_min_version(Version::Tls13)
+            .native_tls_unsupported()
+            .is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::timer::sleep;
+use std::{future::Future, time::Duration};
+
+/// A data message of a WebSocket, pings from the server are answered by the socket itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Message {
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Binary(bytes) => bytes,
+        }
+    }
+
+    /// A text message holding `value` as JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json<T: serde::Serialize>(value: &T) -> serde_json::Result<Self> {
+        Ok(Self::Text(serde_json::to_string(value)?))
+    }
+
+    /// Deserializes a text or binary message as JSON.
+    #[cfg(feature = "serde")]
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(self.as_bytes())
+    }
+
+    /// A binary message holding `value` as msgpack, with named fields.
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack<T: serde::Serialize>(value: &T) -> Result<Self, rmp_serde::encode::Error> {
+        Ok(Self::Binary(rmp_serde::to_vec_named(value)?))
+    }
+
+    #[cfg(feature = "msgpack")]
+    pub fn msgpack<T: serde::de::DeserializeOwned>(&self) -> Result<T, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(self.as_bytes())
+    }
+}
+
+/// What [`Connect::receive`] read from a socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incoming {
+    Message(Message),
+    /// The answer to a ping.
+    Pong,
+}
+
+/// An engine that opens a [`WebSocket`].
+pub trait Connect {
+    /// An open connection.
+    type Socket;
+    type Error;
+    /// Whether pongs are received, browsers answer pings without telling.
+    const PONGS: bool = true;
+
+    /// Upgrades a connection to `path`, with the URL and settings of the engine.
+    fn connect(&self, path: &str) -> impl Future<Output = Result<Self::Socket, Self::Error>>;
+
+    fn send(
+        socket: &mut Self::Socket,
+        message: Message,
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// The next data message or pong, `None` once the connection was closed.
+    fn receive(
+        socket: &mut Self::Socket,
+    ) -> impl Future<Output = Option<Result<Incoming, Self::Error>>>;
+
+    fn ping(socket: &mut Self::Socket) -> impl Future<Output = Result<(), Self::Error>>;
+
+    fn close(socket: &mut Self::Socket) -> impl Future<Output = ()>;
+}
+
+/// A WebSocket connected on first use, created by the `websocket` method of an engine.
+///
+/// Without a `reconnect` delay the socket stays closed once the connection ends, with one it
+/// connects again and sends the `greeting` messages first.
+pub struct WebSocket<C: Connect> {
+    client: C,
+    path: String,
+    socket: Option<C::Socket>,
+    connected: bool,
+    closed: bool,
+    /// A ping was sent and nothing was received since.
+    pinged: bool,
+    /// Sent after every connection, such as an authentication message.
+    pub greeting: Vec<Message>,
+    /// The delay before connecting again after the connection ended.
+    pub reconnect: Option<Duration>,
+    /// Pings the server while no message arrives, browsers send their own pings instead.
+    pub ping_interval: Option<Duration>,
+    /// How long to wait for anything after a ping, the connection is dropped as dead after
+    /// it and connects again when reconnecting.
+    pub pong_timeout: Option<Duration>,
+}
+
+impl<C: Connect> WebSocket<C> {
+    pub(crate) const fn new(client: C, path: String) -> Self {
+        Self {
+            client,
+            path,
+            socket: None,
+            connected: false,
+            closed: false,
+            pinged: false,
+            greeting: Vec::new(),
+            reconnect: None,
+            ping_interval: Some(Duration::from_secs(30)),
+            pong_timeout: Some(Duration::from_secs(10)),
+        }
+    }
+
+    /// Connects unless a connection is open already, also after the socket was closed.
+    pub async fn connect(&mut self) -> Result<(), C::Error> {
+        if self.socket.is_some() {
+            return Ok(());
+        }
+        if self.connected {
+            if let Some(delay) = self.reconnect {
+                sleep(delay).await;
+            }
+        }
+        self.connected = true;
+        self.closed = false;
+        self.pinged = false;
+
+        let mut socket = self.client.connect(&self.path).await?;
+        for message in &self.greeting {
+            C::send(&mut socket, message.clone()).await?;
+        }
+        self.socket = Some(socket);
+        Ok(())
+    }
+
+    /// Connects first when no connection is open.
+    pub async fn send(&mut self, message: Message) -> Result<(), C::Error> {
+        self.connect().await?;
+        let Some(socket) = &mut self.socket else {
+            return Ok(());
+        };
+        let result = C::send(socket, message).await;
+        if result.is_err() {
+            self.socket = None;
+        }
+        result
+    }
+
+    /// The next data message, `None` once the connection ended without a `reconnect` delay.
+    ///
+    /// Errors end the connection, the next call connects again when reconnecting.
+    pub async fn next(&mut self) -> Option<Result<Message, C::Error>> {
+        loop {
+            if self.closed {
+                return None;
+            }
+            let Some(socket) = &mut self.socket else {
+                if let Err(error) = self.connect().await {
+                    self.closed = self.reconnect.is_none();
+                    return Some(Err(error));
+                }
+                continue;
+            };
+
+            let timeout = if self.pinged {
+                self.pong_timeout
+            } else {
+                self.ping_interval
+            };
+            let received = match timeout {
+                Some(timeout) => tokio::select! {
+                    biased;
+                    received = C::receive(socket) => Some(received),
+                    () = sleep(timeout) => None,
+                },
+                None => Some(C::receive(socket).await),
+            };
+            let Some(received) = received else {
+                // nothing arrived since the ping, the connection is gone without a close
+                if self.pinged {
+                    self.socket = None;
+                    self.closed = self.reconnect.is_none();
+                    continue;
+                }
+                if let Err(error) = C::ping(socket).await {
+                    self.socket = None;
+                    self.closed = self.reconnect.is_none();
+                    return Some(Err(error));
+                }
+                self.pinged = C::PONGS && self.pong_timeout.is_some();
+                continue;
+            };
+            self.pinged = false;
+
+            match received {
+                Some(Ok(Incoming::Message(message))) => return Some(Ok(message)),
+                Some(Ok(Incoming::Pong)) => {}
+                Some(Err(error)) => {
+                    self.socket = None;
+                    self.closed = self.reconnect.is_none();
+                    return Some(Err(error));
+                }
+                None => {
+                    self.socket = None;
+                    self.closed = self.reconnect.is_none();
+                }
+            }
+        }
+    }
+
+    /// Closes the connection, `next` returns `None` until the socket connects again.
+    pub async fn close(&mut self) {
+        self.closed = true;
+        if let Some(mut socket) = self.socket.take() {
+            C::close(&mut socket).await;
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub async fn send_json<T: serde::Serialize + Sync>(&mut self, value: &T) -> Result<(), C::Error>
+    where
+        C::Error: From<serde_json::Error>,
+    {
+        self.send(Message::from_json(value)?).await
+    }
+
+    #[cfg(feature = "serde")]
+    pub async fn next_json<T: serde::de::DeserializeOwned>(&mut self) -> Option<Result<T, C::Error>>
+    where
+        C::Error: From<serde_json::Error>,
+    {
+        Some(self.next().await?.and_then(|message| Ok(message.json()?)))
+    }
+
+    #[cfg(feature = "msgpack")]
+    pub async fn send_msgpack<T: serde::Serialize + Sync>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), C::Error>
+    where
+        C::Error: From<rmp_serde::encode::Error>,
+    {
+        self.send(Message::from_msgpack(value)?).await
+    }
+
+    #[cfg(feature = "msgpack")]
+    pub async fn next_msgpack<T: serde::de::DeserializeOwned>(
+        &mut self,
+    ) -> Option<Result<T, C::Error>>
+    where
+        C::Error: From<rmp_serde::decode::Error>,
+    {
+        Some(
+            self.next()
+                .await?
+                .and_then(|message| Ok(message.msgpack()?)),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::websocket::Message;
+
+    #[test]
+    fn typed_messages() -> serde_json::Result<()> {
+        let message = Message::from_json(&serde_json::json!({ "type": "Ping", "data": 0 }))?;
+        assert_eq!(
+            message,
+            Message::Text(String::from(r#"{"data":0,"type":"Ping"}"#))
+        );
+        assert_eq!(message.json::<serde_json::Value>()?["type"], "Ping");
+
+        #[cfg(feature = "msgpack")]
+        {
+            let message = Message::from_msgpack(&serde_json::json!({ "type": "Ping" }))
+                .map_err(serde::ser::Error::custom)?;
+            assert!(matches!(message, Message::Binary(_)));
+            assert_eq!(
+                message
+                    .msgpack::<serde_json::Value>()
+                    .map_err(serde::de::Error::custom)?["type"],
+                "Ping"
+            );
+        }
+        Ok(())
+    }
+}